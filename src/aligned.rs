@@ -0,0 +1,271 @@
+use ::std::fmt;
+use ::std::mem;
+use ::std::slice;
+use ::std::iter::FromIterator;
+use ::std::ops::{Deref, DerefMut};
+use ::packed::{Packed, PrimitivePackable};
+
+/// A growable buffer of primitive scalars, laid out for aligned SIMD loads and stores.
+///
+/// The storage is a `Vec` of `T::Vector`, so it is aligned to the vector's size, and
+/// its capacity is always a multiple of `T::Vector::WIDTH`.  The lanes past `len()` (up to
+/// the next multiple of the width) are padding.  They are zero, except for anything
+/// written through `padded_mut` or `as_vectors_mut` (see `clear_padding`).
+///
+/// It derefs to `[T]`, so it can be used anywhere a `ScalarSlice` is expected;
+/// `padded()` and `padded_mut()` additionally include the padding, so that every chunk
+/// (including the last) can be loaded with `Packed::load_aligned`.
+///
+/// (this is only for primitive scalars, which are the only types whose vectors
+///  are laid out like arrays of scalars.  For VLists and custom types, see `SoaVec`)
+pub struct AlignedVec<T: PrimitivePackable> {
+    vectors: Vec<T::Vector>,
+    len: usize,
+}
+
+impl<T: PrimitivePackable> AlignedVec<T> {
+    #[inline(always)]
+    fn width() -> usize {
+        <T::Vector as Packed>::WIDTH
+    }
+
+    pub fn new() -> Self {
+        debug_assert_eq!(mem::size_of::<T::Vector>(), mem::size_of::<T>() * Self::width());
+        AlignedVec { vectors: Vec::new(), len: 0 }
+    }
+
+    /// Creates an empty buffer with room for at least `capacity` scalars.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut out = Self::new();
+        out.reserve(capacity);
+        out
+    }
+
+    /// Creates a buffer holding `len` copies of `value`.
+    pub fn from_elem(value: T, len: usize) -> Self {
+        let mut out = Self::new();
+        out.vectors = vec![T::Vector::splat(value); div_ceil(len, Self::width())];
+        out.len = len;
+        out.clear_padding();
+        out
+    }
+
+    /// The number of scalars, not counting padding.
+    #[inline]
+    pub fn len(&self) -> usize { self.len }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// The number of scalars that fit without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize { self.vectors.capacity() * Self::width() }
+
+    pub fn reserve(&mut self, additional: usize) {
+        let vectors = div_ceil(self.len + additional, Self::width());
+        let len = self.vectors.len();
+        self.vectors.reserve(vectors.saturating_sub(len));
+    }
+
+    pub fn push(&mut self, value: T) {
+        if self.len == self.padded_len() {
+            self.vectors.push(<T::Vector as Packed>::default());
+        }
+        let len = self.len;
+        self.len += 1;
+        self[len] = value;
+        self.clear_padding();
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let value = self.vectors[(self.len - 1) / Self::width()].extract((self.len - 1) % Self::width());
+        self.truncate(self.len - 1);
+        Some(value)
+    }
+
+    /// Shortens the buffer to `len` scalars (and does nothing if it's already shorter).
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            self.len = len;
+            self.vectors.truncate(div_ceil(len, Self::width()));
+            self.clear_padding();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    pub fn extend_from_slice(&mut self, values: &[T]) {
+        self.extend(values.iter().cloned());
+    }
+
+    /// The scalars, including the padding at the end.
+    ///
+    /// The length is always a multiple of `T::Vector::WIDTH`.
+    #[inline]
+    pub fn padded(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.vectors.as_ptr() as *const T, self.padded_len()) }
+    }
+
+    /// The scalars, including the padding at the end.
+    ///
+    /// Anything written to the padding stays there until the length changes
+    /// (or `clear_padding` is called), at which point it is reset to zero.
+    #[inline]
+    pub fn padded_mut(&mut self) -> &mut [T] {
+        let len = self.padded_len();
+        unsafe { slice::from_raw_parts_mut(self.vectors.as_mut_ptr() as *mut T, len) }
+    }
+
+    /// The underlying vectors.  The last one may contain padding.
+    #[inline]
+    pub fn as_vectors(&self) -> &[T::Vector] { &self.vectors }
+
+    /// The underlying vectors.  The last one may contain padding.
+    ///
+    /// (see `padded_mut` regarding writes to the padding)
+    #[inline]
+    pub fn as_vectors_mut(&mut self) -> &mut [T::Vector] { &mut self.vectors }
+
+    /// Resets the padding to zero, after it was written through `padded_mut` or `as_vectors_mut`.
+    pub fn clear_padding(&mut self) {
+        let used = self.len % Self::width();
+        if used == 0 {
+            return;
+        }
+        let zero = <T::Vector as Packed>::default().extract(0);
+        let last = self.vectors.last_mut().expect("(bug) padding without vectors");
+        for i in used..Self::width() {
+            *last = last.clone().replace(i, zero);
+        }
+    }
+
+    #[inline(always)]
+    fn padded_len(&self) -> usize {
+        self.vectors.len() * Self::width()
+    }
+}
+
+#[inline(always)]
+fn div_ceil(a: usize, b: usize) -> usize {
+    (a + b - 1) / b
+}
+
+impl<T: PrimitivePackable> Deref for AlignedVec<T> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        &self.padded()[..self.len]
+    }
+}
+
+impl<T: PrimitivePackable> DerefMut for AlignedVec<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        let len = self.len;
+        &mut self.padded_mut()[..len]
+    }
+}
+
+impl<T: PrimitivePackable> Default for AlignedVec<T> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<T: PrimitivePackable> Clone for AlignedVec<T> {
+    fn clone(&self) -> Self {
+        AlignedVec { vectors: self.vectors.clone(), len: self.len }
+    }
+}
+
+impl<T: PrimitivePackable + fmt::Debug> fmt::Debug for AlignedVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: PrimitivePackable + PartialEq> PartialEq for AlignedVec<T> {
+    fn eq(&self, other: &Self) -> bool { **self == **other }
+}
+
+impl<T: PrimitivePackable> Extend<T> for AlignedVec<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T: PrimitivePackable> FromIterator<T> for AlignedVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut out = Self::new();
+        out.extend(iter);
+        out
+    }
+}
+
+impl<'a, T: PrimitivePackable> From<&'a [T]> for AlignedVec<T> {
+    fn from(values: &'a [T]) -> Self {
+        let mut out = Self::new();
+        out.extend_from_slice(values);
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::faster::f32s;
+
+    #[test]
+    fn alignment_and_padding() {
+        let mut xs: AlignedVec<f32> = (0..f32s::WIDTH + 1).map(|x| x as f32).collect();
+        assert_eq!(xs.len(), f32s::WIDTH + 1);
+        assert_eq!(xs.padded().len(), 2 * f32s::WIDTH);
+        assert_eq!(xs.as_ptr() as usize % mem::size_of::<f32s>(), 0);
+        assert_eq!(xs[f32s::WIDTH], f32s::WIDTH as f32);
+        assert!(xs.padded()[f32s::WIDTH + 1..].iter().all(|&x| x == 0.0));
+
+        let v = f32s::load_aligned(&xs.padded()[f32s::WIDTH..]);
+        assert_eq!(v.extract(0), f32s::WIDTH as f32);
+
+        xs[1] = 10.0;
+        assert_eq!(xs.pop(), Some(f32s::WIDTH as f32));
+        assert_eq!(xs.padded().len(), f32s::WIDTH);
+        xs.truncate(1);
+        assert_eq!(&xs[..], &[0.0]);
+        assert!(xs.padded()[1..].iter().all(|&x| x == 0.0));
+
+        xs.padded_mut()[1] = 5.0;
+        assert_eq!(xs.padded()[1], 5.0);
+        xs.clear_padding();
+        assert_eq!(&xs[..], &[0.0]);
+        assert!(xs.padded()[1..].iter().all(|&x| x == 0.0));
+
+        // (pushing changes the length too)
+        for x in &mut xs.padded_mut()[1..] {
+            *x = 5.0;
+        }
+        xs.push(1.0);
+        assert_eq!(&xs[..], &[0.0, 1.0]);
+        assert!(xs.padded()[2..].iter().all(|&x| x == 0.0));
+        xs.as_vectors_mut()[0] = f32s::splat(5.0);
+        xs.extend_from_slice(&[2.0]);
+        assert_eq!(&xs[..], &[5.0, 5.0, 2.0]);
+        assert!(xs.padded()[3..].iter().all(|&x| x == 0.0));
+    }
+
+    #[test]
+    fn from_elem() {
+        let xs = AlignedVec::from_elem(3u8, 5);
+        assert_eq!(&xs[..], &[3; 5]);
+        assert!(xs.padded()[5..].iter().all(|&x| x == 0));
+        assert_eq!(AlignedVec::from(&xs[..]), xs);
+    }
+}
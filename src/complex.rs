@@ -0,0 +1,278 @@
+use ::std::slice;
+use ::std::ops::{Add, Sub, Mul, Div, Neg};
+use ::packed::{Packed, Packable};
+use ::math::PackedFloat;
+use ::interleave::{PackedInterleaved, Deinterleave};
+use ::iter::{PackedSlice, PackedSliceMut};
+
+/// A complex number (`Complex<f64>`), or a vector of them (`Complex<f64s>`).
+///
+/// `Complex<f32>` and `Complex<f64>` are `Packable`, so they can be stored in a `SoaVec`.
+/// Slices of them are array-of-structs data, which `simd_iter` and friends convert
+/// with shuffles (see `Complex::load_interleaved`).
+///
+/// The arithmetic operators and the methods are only implemented for vectors.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, CustomPacked)]
+#[custom_packed(root = "", ops = "Add, Sub, Neg")]
+pub struct Complex<V> {
+    pub real: V,
+    pub imag: V,
+}
+
+impl<V> Complex<V> {
+    #[inline(always)]
+    pub fn new(real: V, imag: V) -> Self {
+        Complex { real, imag }
+    }
+}
+
+impl<V> Complex<V>
+where
+    V: PackedFloat + Copy + Add<Output = V> + Sub<Output = V> + Mul<Output = V> + Div<Output = V> + Neg<Output = V>,
+{
+    /// `r * (cos(theta) + i sin(theta))`
+    #[inline(always)]
+    pub fn from_polar(r: V, theta: V) -> Self {
+        Complex { real: r * theta.cos(), imag: r * theta.sin() }
+    }
+
+    /// `(abs, arg)`
+    #[inline(always)]
+    pub fn to_polar(self) -> (V, V) {
+        (self.abs(), self.arg())
+    }
+
+    #[inline(always)]
+    pub fn conj(self) -> Self {
+        Complex { real: self.real, imag: -self.imag }
+    }
+
+    /// `abs()` squared, which avoids the square root.
+    #[inline(always)]
+    pub fn norm_sqr(self) -> V {
+        self.real * self.real + self.imag * self.imag
+    }
+
+    /// The magnitude.
+    ///
+    /// (this is computed as `norm_sqr().sqrt()`, so it overflows to infinity for
+    ///  components larger than about the square root of the largest float)
+    #[inline(always)]
+    pub fn abs(self) -> V {
+        self.norm_sqr().sqrt()
+    }
+
+    /// The angle from the positive real axis, in `[-pi, pi]`.
+    #[inline(always)]
+    pub fn arg(self) -> V {
+        self.imag.atan2(self.real)
+    }
+
+    /// `e` to the power of `self`.
+    #[inline(always)]
+    pub fn exp(self) -> Self {
+        Complex::from_polar(self.real.exp(), self.imag)
+    }
+}
+
+impl<V> Mul for Complex<V>
+where
+    V: Packed + Copy + Add<Output = V> + Sub<Output = V> + Mul<Output = V>,
+{
+    type Output = Self;
+
+    #[inline(always)]
+    fn mul(self, other: Self) -> Self {
+        Complex {
+            real: self.real * other.real - self.imag * other.imag,
+            imag: self.real * other.imag + self.imag * other.real,
+        }
+    }
+}
+
+// (the textbook formula, which can overflow for very large divisors)
+impl<V> Div for Complex<V>
+where
+    V: Packed + Copy + Add<Output = V> + Sub<Output = V> + Mul<Output = V> + Div<Output = V>,
+{
+    type Output = Self;
+
+    #[inline(always)]
+    fn div(self, other: Self) -> Self {
+        let denom = other.real * other.real + other.imag * other.imag;
+        Complex {
+            real: (self.real * other.real + self.imag * other.imag) / denom,
+            imag: (self.imag * other.real - self.real * other.imag) / denom,
+        }
+    }
+}
+
+impl<V> Complex<V>
+where
+    V: Deinterleave,
+    V::Scalar: Copy,
+{
+    /// Loads `WIDTH` complex numbers from an array of structs, using shuffles.
+    ///
+    /// (this shadows `PackedInterleaved::load_interleaved`, which does the same thing
+    ///  lane by lane for custom types)
+    ///
+    /// Panics if the slice is too short.
+    #[inline(always)]
+    pub fn load_interleaved(data: &[Complex<V::Scalar>]) -> Self {
+        let [real, imag] = <[V; 2]>::load_interleaved(as_pairs(data));
+        Complex { real, imag }
+    }
+
+    /// Stores `WIDTH` complex numbers into an array of structs, using shuffles.
+    ///
+    /// Panics if the slice is too short.
+    #[inline(always)]
+    pub fn store_interleaved(self, data: &mut [Complex<V::Scalar>]) {
+        [self.real, self.imag].store_interleaved(as_pairs_mut(data))
+    }
+}
+
+// (these are sound because Complex is repr(C))
+#[inline(always)]
+fn as_pairs<T>(data: &[Complex<T>]) -> &[[T; 2]] {
+    unsafe { slice::from_raw_parts(data.as_ptr() as *const [T; 2], data.len()) }
+}
+
+#[inline(always)]
+fn as_pairs_mut<T>(data: &mut [Complex<T>]) -> &mut [[T; 2]] {
+    unsafe { slice::from_raw_parts_mut(data.as_mut_ptr() as *mut [T; 2], data.len()) }
+}
+
+macro_rules! impl_complex_packable {
+    ($($Scalar:ident: $Vector:ident,)*) => {$(
+        impl Packable for Complex<$Scalar> {
+            type Vector = Complex<::faster::$Vector>;
+        }
+
+        impl<'a> PackedSlice<'a> for &'a [Complex<$Scalar>] {
+            type Vector = Complex<::faster::$Vector>;
+
+            #[inline(always)]
+            fn len(&self) -> usize { <[_]>::len(self) }
+
+            #[inline(always)]
+            fn split_at(self, mid: usize) -> (Self, Self) { <[_]>::split_at(self, mid) }
+
+            #[inline(always)]
+            fn get(&self, idx: usize) -> Complex<$Scalar> { self[idx] }
+
+            #[inline(always)]
+            unsafe fn load_unchecked(self) -> Self::Vector {
+                Complex::load_interleaved(self)
+            }
+        }
+
+        impl<'a> PackedSliceMut<'a> for &'a mut [Complex<$Scalar>] {
+            type Vector = Complex<::faster::$Vector>;
+
+            #[inline(always)]
+            fn len(&self) -> usize { <[_]>::len(self) }
+
+            #[inline(always)]
+            fn split_at_mut(self, mid: usize) -> (Self, Self) { <[_]>::split_at_mut(self, mid) }
+
+            #[inline(always)]
+            fn get(&self, idx: usize) -> Complex<$Scalar> { self[idx] }
+
+            #[inline(always)]
+            fn set(&mut self, idx: usize, value: Complex<$Scalar>) { self[idx] = value; }
+
+            #[inline(always)]
+            unsafe fn load_unchecked(&self) -> Self::Vector {
+                Complex::load_interleaved(&self[..])
+            }
+
+            #[inline(always)]
+            unsafe fn store_unchecked(&mut self, vector: Self::Vector) {
+                vector.store_interleaved(&mut self[..])
+            }
+        }
+    )*};
+}
+
+impl_complex_packable!{
+    f32: f32s,
+    f64: f64s,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::std::f64::consts::PI;
+    use ::faster::{f32s, f64s};
+    use ::iter::SimdIterator;
+    use ::soa::SoaVec;
+
+    fn splat(real: f64, imag: f64) -> Complex<f64s> {
+        Complex::splat(Complex::new(real, imag))
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    fn assert_close(a: Complex<f64>, b: Complex<f64>) {
+        assert!(close(a.real, b.real) && close(a.imag, b.imag), "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let z = splat(1.0, 2.0);
+        let w = splat(3.0, -1.0);
+        assert_eq!((z + w).extract(0), Complex::new(4.0, 1.0));
+        assert_eq!((z * w).extract(0), Complex::new(5.0, 5.0));
+        assert_close((z * w / w).extract(0), Complex::new(1.0, 2.0));
+        assert_eq!((z * z.conj()).extract(0), Complex::new(5.0, 0.0));
+        assert_eq!(z.norm_sqr(), f64s::splat(5.0));
+    }
+
+    #[test]
+    fn polar() {
+        let z = splat(-1.0, 1.0);
+        assert!(close(z.abs().extract(0), 2f64.sqrt()));
+        assert!(close(z.arg().extract(0), 0.75 * PI));
+
+        let (r, theta) = z.to_polar();
+        assert_close(Complex::from_polar(r, theta).extract(0), Complex::new(-1.0, 1.0));
+
+        // e^(i pi) = -1
+        assert_close(splat(0.0, PI).exp().extract(0), Complex::new(-1.0, 0.0));
+        assert_close(splat(1.0, 0.0).exp().extract(0), Complex::new(1f64.exp(), 0.0));
+    }
+
+    #[test]
+    fn slices() {
+        let n = 2 * f32s::WIDTH + 1;
+        let zs: Vec<_> = (0..n).map(|i| Complex::new(i as f32, -(i as f32))).collect();
+
+        let first = Complex::<f32s>::load_interleaved(&zs);
+        assert_eq!(first.imag.extract(1), -1.0);
+        assert_eq!(first.extract(2), zs[2]);
+
+        let mut iter = (&zs[..]).simd_iter();
+        let sum = iter.by_ref().fold(Complex::splat(Complex::new(0.0, 0.0)), |acc, z| acc + z);
+        assert_eq!(sum.extract(1), Complex::new((1 + 1 + f32s::WIDTH) as f32, -((1 + 1 + f32s::WIDTH) as f32)));
+        assert_eq!(iter.remainder(), &zs[n - 1..]);
+
+        let mut ws = zs.clone();
+        (&mut ws[..]).simd_map_in_place(|z| z * z.conj());
+        for (z, w) in zs.iter().zip(&ws) {
+            assert_eq!(*w, Complex::new(2.0 * z.real * z.real, 0.0));
+        }
+    }
+
+    #[test]
+    fn soa() {
+        let soa: SoaVec<Complex<f64>> = (0..3).map(|i| Complex::new(i as f64, 1.0)).collect();
+        let vlist_pat![reals, imags] = soa.columns();
+        assert_eq!(reals, &[0.0, 1.0, 2.0]);
+        assert_eq!(imags, &[1.0; 3]);
+    }
+}
@@ -0,0 +1,59 @@
+use ::typelevel::{TypeLevelUsize, U2, U4, U8, U16, U32, U64};
+use ::faster::vecs::{u8x16, u8x32, u8x64, u16x8, u16x16, u16x32};
+use ::faster::vecs::{u32x4, u32x8, u32x16, u64x2, u64x4, u64x8};
+use ::std::simd::{u8x2, u8x4, u8x8, u16x2, u16x4, u32x2};
+
+/// A vector of indices, for `Packed::gather` and `Packed::scatter`.
+///
+/// Implemented on the unsigned integer vectors in std.  Lane `i` of the index vector
+/// is the position of lane `i` of the `Packed` value in the slice (or in every slice, for
+/// VLists and custom types), so its width must match.
+///
+/// (since the unsigned vectors are also `Packed`, having both traits in scope makes
+///  `WIDTH` ambiguous on them; bounds like `I: index::IndexVector` avoid importing it)
+pub trait IndexVector: Copy {
+    type Width: TypeLevelUsize;
+    const WIDTH: usize = Self::Width::VALUE;
+
+    /// The index held in lane `lane`.
+    ///
+    /// Panics if `lane >= WIDTH`.
+    fn index(self, lane: usize) -> usize;
+
+    unsafe fn index_unchecked(self, lane: usize) -> usize;
+}
+
+macro_rules! impl_index_vector {
+    ($($Vector:ident: $Width:ty,)*) => {$(
+        impl IndexVector for $Vector {
+            type Width = $Width;
+
+            #[inline(always)]
+            fn index(self, lane: usize) -> usize { self.extract(lane) as usize }
+
+            #[inline(always)]
+            unsafe fn index_unchecked(self, lane: usize) -> usize { self.extract_unchecked(lane) as usize }
+        }
+    )*};
+}
+
+impl_index_vector!{
+    u8x2: U2,
+    u8x4: U4,
+    u8x8: U8,
+    u8x16: U16,
+    u8x32: U32,
+    u8x64: U64,
+    u16x2: U2,
+    u16x4: U4,
+    u16x8: U8,
+    u16x16: U16,
+    u16x32: U32,
+    u32x2: U2,
+    u32x4: U4,
+    u32x8: U8,
+    u32x16: U16,
+    u64x2: U2,
+    u64x4: U4,
+    u64x8: U8,
+}
@@ -0,0 +1,367 @@
+use ::std::slice;
+use ::vlist::Cons;
+use ::packed::{Packed, PrimitivePacked, CustomPacked};
+use ::intrinsics::{simd_shuffle2, simd_shuffle4, simd_shuffle8};
+use ::intrinsics::{simd_shuffle16, simd_shuffle32, simd_shuffle64};
+use ::std::simd::{f32x2, i8x2, i8x4, i8x8, i16x2, i16x4, i32x2};
+use ::std::simd::{u8x2, u8x4, u8x8, u16x2, u16x4, u32x2};
+use ::faster::vecs::{f32x4, f32x8, f32x16, f64x2, f64x4, f64x8};
+use ::faster::vecs::{i8x16, i8x32, i8x64, i16x8, i16x16, i16x32};
+use ::faster::vecs::{i32x4, i32x8, i32x16, i64x2, i64x4, i64x8};
+use ::faster::vecs::{u8x16, u8x32, u8x64, u16x8, u16x16, u16x32};
+use ::faster::vecs::{u32x4, u32x8, u32x16, u64x2, u64x4, u64x8};
+
+/// Loads and stores between a `Packed` type and an array-of-structs.
+///
+/// Where `load_unaligned` on e.g. `VList![f64s, f64s]` takes one slice per field,
+/// `load_interleaved` takes a single slice of `VList![f64, f64]`s and splits each field
+/// out into its own vector.
+///
+/// Implemented on:
+/// * primitive vectors (where it's just an unaligned load or store)
+/// * arrays of primitive vectors, e.g. `[f32s; 3]` loads from `&[[f32; 3]]`.
+///   Arrays of 2 or 4 vectors are (de)interleaved with shuffles.
+/// * VLists and `CustomPacked` types, which are (de)interleaved one lane at a time.
+///   This is so even when every field has the same vector type, as neither `Cons` nor
+///   the scalar of a `CustomPacked` type has a layout that could be shuffled like an array's.
+pub trait PackedInterleaved: Packed {
+    /// Load the first `WIDTH` elements of `data`.
+    ///
+    /// Panics if `data` is too short.
+    fn load_interleaved(data: &[Self::Scalar]) -> Self;
+
+    /// Store into the first `WIDTH` elements of `data`.
+    ///
+    /// Panics if `data` is too short.
+    fn store_interleaved(self, data: &mut [Self::Scalar]);
+}
+
+#[inline(always)]
+fn load_lanes<V>(data: &[V::Scalar]) -> V
+where
+    V: Packed,
+    V::Scalar: Clone,
+{
+    assert!(data.len() >= V::WIDTH, "load_interleaved: slice is too short");
+    let mut vector = V::default();
+    for (i, x) in data[..V::WIDTH].iter().enumerate() {
+        vector = unsafe { vector.replace_unchecked(i, x.clone()) };
+    }
+    vector
+}
+
+#[inline(always)]
+fn store_lanes<V>(vector: V, data: &mut [V::Scalar])
+where
+    V: Packed,
+{
+    assert!(data.len() >= V::WIDTH, "store_interleaved: slice is too short");
+    for (i, x) in data[..V::WIDTH].iter_mut().enumerate() {
+        *x = unsafe { vector.extract_unchecked(i) };
+    }
+}
+
+/// View a slice of arrays as a flat slice of their elements.
+#[inline(always)]
+fn flatten<T, A: AsRef<[T]>>(data: &[A], n: usize) -> &[T] {
+    debug_assert!(data.iter().all(|a| a.as_ref().len() == n));
+    unsafe { slice::from_raw_parts(data.as_ptr() as *const T, data.len() * n) }
+}
+
+#[inline(always)]
+fn flatten_mut<T, A: AsMut<[T]>>(data: &mut [A], n: usize) -> &mut [T] {
+    debug_assert!(data.iter_mut().all(|a| a.as_mut().len() == n));
+    unsafe { slice::from_raw_parts_mut(data.as_mut_ptr() as *mut T, data.len() * n) }
+}
+
+//--------------------------------------------------------------------------------
+
+/// Shuffles that split and join the even and odd lanes of two vectors.
+pub trait Deinterleave: PrimitivePacked + PackedInterleaved + Copy {
+    /// `([a0 a1 a2 a3], [b0 b1 b2 b3])` becomes `([a0 a2 b0 b2], [a1 a3 b1 b3])`.
+    fn deinterleave(a: Self, b: Self) -> (Self, Self);
+
+    /// `([a0 a1 a2 a3], [b0 b1 b2 b3])` becomes `([a0 b0 a1 b1], [a2 b2 a3 b3])`.
+    ///
+    /// This undoes `deinterleave`.
+    fn interleave(a: Self, b: Self) -> (Self, Self);
+}
+
+macro_rules! impl_deinterleave {
+    ($($shuffle:ident, $EVENS:ident, $ODDS:ident, $LOWS:ident, $HIGHS:ident: [$($Vector:ident),*];)*) => {$($(
+        impl Deinterleave for $Vector {
+            #[inline(always)]
+            fn deinterleave(a: Self, b: Self) -> (Self, Self) {
+                unsafe { ($shuffle(a, b, $EVENS), $shuffle(a, b, $ODDS)) }
+            }
+
+            #[inline(always)]
+            fn interleave(a: Self, b: Self) -> (Self, Self) {
+                unsafe { ($shuffle(a, b, $LOWS), $shuffle(a, b, $HIGHS)) }
+            }
+        }
+
+        impl PackedInterleaved for $Vector {
+            #[inline(always)]
+            fn load_interleaved(data: &[Self::Scalar]) -> Self {
+                Self::load_unaligned(data)
+            }
+
+            #[inline(always)]
+            fn store_interleaved(self, data: &mut [Self::Scalar]) {
+                self.store_unaligned(data)
+            }
+        }
+    )*)*};
+}
+
+impl_deinterleave!{
+    simd_shuffle2, EVENS_2, ODDS_2, LOWS_2, HIGHS_2: [f32x2, f64x2, i8x2, u8x2, i16x2, u16x2, i32x2, u32x2, i64x2, u64x2];
+    simd_shuffle4, EVENS_4, ODDS_4, LOWS_4, HIGHS_4: [f32x4, f64x4, i8x4, u8x4, i16x4, u16x4, i32x4, u32x4, i64x4, u64x4];
+    simd_shuffle8, EVENS_8, ODDS_8, LOWS_8, HIGHS_8: [f32x8, f64x8, i8x8, u8x8, i16x8, u16x8, i32x8, u32x8, i64x8, u64x8];
+    simd_shuffle16, EVENS_16, ODDS_16, LOWS_16, HIGHS_16: [f32x16, i8x16, u8x16, i16x16, u16x16, i32x16, u32x16];
+    simd_shuffle32, EVENS_32, ODDS_32, LOWS_32, HIGHS_32: [i8x32, u8x32, i16x32, u16x32];
+    simd_shuffle64, EVENS_64, ODDS_64, LOWS_64, HIGHS_64: [i8x64, u8x64];
+}
+
+const EVENS_2: [u32; 2] = [0, 2];
+const ODDS_2: [u32; 2] = [1, 3];
+const LOWS_2: [u32; 2] = [0, 2];
+const HIGHS_2: [u32; 2] = [1, 3];
+
+const EVENS_4: [u32; 4] = [0, 2, 4, 6];
+const ODDS_4: [u32; 4] = [1, 3, 5, 7];
+const LOWS_4: [u32; 4] = [0, 4, 1, 5];
+const HIGHS_4: [u32; 4] = [2, 6, 3, 7];
+
+const EVENS_8: [u32; 8] = [0, 2, 4, 6, 8, 10, 12, 14];
+const ODDS_8: [u32; 8] = [1, 3, 5, 7, 9, 11, 13, 15];
+const LOWS_8: [u32; 8] = [0, 8, 1, 9, 2, 10, 3, 11];
+const HIGHS_8: [u32; 8] = [4, 12, 5, 13, 6, 14, 7, 15];
+
+const EVENS_16: [u32; 16] = [0, 2, 4, 6, 8, 10, 12, 14, 16, 18, 20, 22, 24, 26, 28, 30];
+const ODDS_16: [u32; 16] = [1, 3, 5, 7, 9, 11, 13, 15, 17, 19, 21, 23, 25, 27, 29, 31];
+const LOWS_16: [u32; 16] = [0, 16, 1, 17, 2, 18, 3, 19, 4, 20, 5, 21, 6, 22, 7, 23];
+const HIGHS_16: [u32; 16] = [8, 24, 9, 25, 10, 26, 11, 27, 12, 28, 13, 29, 14, 30, 15, 31];
+
+const EVENS_32: [u32; 32] = [
+    0, 2, 4, 6, 8, 10, 12, 14, 16, 18, 20, 22, 24, 26, 28, 30,
+    32, 34, 36, 38, 40, 42, 44, 46, 48, 50, 52, 54, 56, 58, 60, 62,
+];
+const ODDS_32: [u32; 32] = [
+    1, 3, 5, 7, 9, 11, 13, 15, 17, 19, 21, 23, 25, 27, 29, 31,
+    33, 35, 37, 39, 41, 43, 45, 47, 49, 51, 53, 55, 57, 59, 61, 63,
+];
+const LOWS_32: [u32; 32] = [
+    0, 32, 1, 33, 2, 34, 3, 35, 4, 36, 5, 37, 6, 38, 7, 39,
+    8, 40, 9, 41, 10, 42, 11, 43, 12, 44, 13, 45, 14, 46, 15, 47,
+];
+const HIGHS_32: [u32; 32] = [
+    16, 48, 17, 49, 18, 50, 19, 51, 20, 52, 21, 53, 22, 54, 23, 55,
+    24, 56, 25, 57, 26, 58, 27, 59, 28, 60, 29, 61, 30, 62, 31, 63,
+];
+
+const EVENS_64: [u32; 64] = [
+    0, 2, 4, 6, 8, 10, 12, 14, 16, 18, 20, 22, 24, 26, 28, 30,
+    32, 34, 36, 38, 40, 42, 44, 46, 48, 50, 52, 54, 56, 58, 60, 62,
+    64, 66, 68, 70, 72, 74, 76, 78, 80, 82, 84, 86, 88, 90, 92, 94,
+    96, 98, 100, 102, 104, 106, 108, 110, 112, 114, 116, 118, 120, 122, 124, 126,
+];
+const ODDS_64: [u32; 64] = [
+    1, 3, 5, 7, 9, 11, 13, 15, 17, 19, 21, 23, 25, 27, 29, 31,
+    33, 35, 37, 39, 41, 43, 45, 47, 49, 51, 53, 55, 57, 59, 61, 63,
+    65, 67, 69, 71, 73, 75, 77, 79, 81, 83, 85, 87, 89, 91, 93, 95,
+    97, 99, 101, 103, 105, 107, 109, 111, 113, 115, 117, 119, 121, 123, 125, 127,
+];
+const LOWS_64: [u32; 64] = [
+    0, 64, 1, 65, 2, 66, 3, 67, 4, 68, 5, 69, 6, 70, 7, 71,
+    8, 72, 9, 73, 10, 74, 11, 75, 12, 76, 13, 77, 14, 78, 15, 79,
+    16, 80, 17, 81, 18, 82, 19, 83, 20, 84, 21, 85, 22, 86, 23, 87,
+    24, 88, 25, 89, 26, 90, 27, 91, 28, 92, 29, 93, 30, 94, 31, 95,
+];
+const HIGHS_64: [u32; 64] = [
+    32, 96, 33, 97, 34, 98, 35, 99, 36, 100, 37, 101, 38, 102, 39, 103,
+    40, 104, 41, 105, 42, 106, 43, 107, 44, 108, 45, 109, 46, 110, 47, 111,
+    48, 112, 49, 113, 50, 114, 51, 115, 52, 116, 53, 117, 54, 118, 55, 119,
+    56, 120, 57, 121, 58, 122, 59, 123, 60, 124, 61, 125, 62, 126, 63, 127,
+];
+
+//--------------------------------------------------------------------------------
+
+impl<V> PackedInterleaved for [V; 2]
+where
+    V: Deinterleave,
+    V::Scalar: Copy,
+{
+    #[inline(always)]
+    fn load_interleaved(data: &[[V::Scalar; 2]]) -> Self {
+        assert!(data.len() >= V::WIDTH, "load_interleaved: slice is too short");
+        let data = flatten(&data[..V::WIDTH], 2);
+        let (a, b) = data.split_at(V::WIDTH);
+        let (x, y) = V::deinterleave(V::load_interleaved(a), V::load_interleaved(b));
+        [x, y]
+    }
+
+    #[inline(always)]
+    fn store_interleaved(self, data: &mut [[V::Scalar; 2]]) {
+        assert!(data.len() >= V::WIDTH, "store_interleaved: slice is too short");
+        let data = flatten_mut(&mut data[..V::WIDTH], 2);
+        let (a, b) = data.split_at_mut(V::WIDTH);
+        let [x, y] = self;
+        let (xy_lo, xy_hi) = V::interleave(x, y);
+        xy_lo.store_interleaved(a);
+        xy_hi.store_interleaved(b);
+    }
+}
+
+impl<V> PackedInterleaved for [V; 4]
+where
+    V: Deinterleave,
+    V::Scalar: Copy,
+{
+    #[inline(always)]
+    fn load_interleaved(data: &[[V::Scalar; 4]]) -> Self {
+        assert!(data.len() >= V::WIDTH, "load_interleaved: slice is too short");
+        let data = flatten(&data[..V::WIDTH], 4);
+        let (ab, cd) = data.split_at(2 * V::WIDTH);
+        let (a, b) = ab.split_at(V::WIDTH);
+        let (c, d) = cd.split_at(V::WIDTH);
+        // two rounds of splitting even and odd lanes
+        let (xz_ab, yw_ab) = V::deinterleave(V::load_interleaved(a), V::load_interleaved(b));
+        let (xz_cd, yw_cd) = V::deinterleave(V::load_interleaved(c), V::load_interleaved(d));
+        let (x, z) = V::deinterleave(xz_ab, xz_cd);
+        let (y, w) = V::deinterleave(yw_ab, yw_cd);
+        [x, y, z, w]
+    }
+
+    #[inline(always)]
+    fn store_interleaved(self, data: &mut [[V::Scalar; 4]]) {
+        assert!(data.len() >= V::WIDTH, "store_interleaved: slice is too short");
+        let data = flatten_mut(&mut data[..V::WIDTH], 4);
+        let (ab, cd) = data.split_at_mut(2 * V::WIDTH);
+        let (a, b) = ab.split_at_mut(V::WIDTH);
+        let (c, d) = cd.split_at_mut(V::WIDTH);
+        let [x, y, z, w] = self;
+        let (xz_ab, xz_cd) = V::interleave(x, z);
+        let (yw_ab, yw_cd) = V::interleave(y, w);
+        let (v_a, v_b) = V::interleave(xz_ab, yw_ab);
+        let (v_c, v_d) = V::interleave(xz_cd, yw_cd);
+        v_a.store_interleaved(a);
+        v_b.store_interleaved(b);
+        v_c.store_interleaved(c);
+        v_d.store_interleaved(d);
+    }
+}
+
+// Other lengths are done lane by lane.  (odd lengths don't split into halves, and 8 and 16
+// simply haven't been written with shuffles)
+macro_rules! impl_lanewise_interleaved_for_arrays {
+    ($($N:expr),*) => {$(
+        impl<V> PackedInterleaved for [V; $N]
+        where
+            V: PrimitivePacked,
+            V::Scalar: Copy,
+        {
+            #[inline(always)]
+            fn load_interleaved(data: &[Self::Scalar]) -> Self {
+                load_lanes(data)
+            }
+
+            #[inline(always)]
+            fn store_interleaved(self, data: &mut [Self::Scalar]) {
+                store_lanes(self, data)
+            }
+        }
+    )*};
+}
+
+impl_lanewise_interleaved_for_arrays!{ 1, 3, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16 }
+
+// (no shuffles here; see the trait docs)
+impl<V, Rest> PackedInterleaved for Cons<V, Rest>
+where
+    Cons<V, Rest>: Packed,
+    <Cons<V, Rest> as Packed>::Scalar: Clone,
+{
+    #[inline(always)]
+    fn load_interleaved(data: &[Self::Scalar]) -> Self {
+        load_lanes(data)
+    }
+
+    #[inline(always)]
+    fn store_interleaved(self, data: &mut [Self::Scalar]) {
+        store_lanes(self, data)
+    }
+}
+
+impl<T> PackedInterleaved for T
+where
+    T: CustomPacked,
+    T::CustomScalar: Clone,
+{
+    #[inline(always)]
+    fn load_interleaved(data: &[Self::Scalar]) -> Self {
+        load_lanes(data)
+    }
+
+    #[inline(always)]
+    fn store_interleaved(self, data: &mut [Self::Scalar]) {
+        store_lanes(self, data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::packed::Packable;
+    use ::complex::Complex;
+    use ::faster::{f32s, f64s, u8s};
+
+    #[test]
+    fn arrays() {
+        let pairs: Vec<[f64; 2]> = (0..f64s::WIDTH).map(|i| [i as f64, -(i as f64)]).collect();
+        let [xs, ys] = <[f64s; 2]>::load_interleaved(&pairs);
+        for i in 0..f64s::WIDTH {
+            assert_eq!((xs.extract(i), ys.extract(i)), (i as f64, -(i as f64)));
+        }
+        let mut out = vec![[0.0; 2]; f64s::WIDTH];
+        [xs, ys].store_interleaved(&mut out);
+        assert_eq!(out, pairs);
+
+        let quads: Vec<[u8; 4]> = (0..u8s::WIDTH as u8).map(|i| [i, i + 1, i + 2, i + 3]).collect();
+        let v = <[u8s; 4]>::load_interleaved(&quads);
+        assert_eq!(v.extract(5), [5, 6, 7, 8]);
+        let mut out = vec![[0; 4]; u8s::WIDTH];
+        v.store_interleaved(&mut out);
+        assert_eq!(out, quads);
+
+        let triples: Vec<[f32; 3]> = (0..f32s::WIDTH).map(|i| [i as f32, 1.0, 2.0]).collect();
+        let v = <[f32s; 3]>::load_interleaved(&triples);
+        assert_eq!(v[0].extract(3), 3.0);
+        assert_eq!(v[2], f32s::splat(2.0));
+    }
+
+    #[test]
+    fn custom() {
+        let cs: Vec<_> = (0..f64s::WIDTH).map(|i| Complex { real: i as f64, imag: 1.0 }).collect();
+        // (Complex shadows these with shuffles of its own)
+        let v = <Complex<f64s> as PackedInterleaved>::load_interleaved(&cs);
+        assert_eq!(v.imag, f64s::splat(1.0));
+        assert_eq!(v.extract(1), cs[1]);
+
+        let mut out = vec![Complex { real: 0.0, imag: 0.0 }; f64s::WIDTH];
+        PackedInterleaved::store_interleaved(v, &mut out);
+        assert_eq!(out, cs);
+
+        let list = vec![vlist![1.0f32, 2u8]; f32s::WIDTH];
+        let v = <VList![f32, u8] as Packable>::Vector::load_interleaved(&list[..]);
+        assert_eq!(v.extract(0), vlist![1.0, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "too short")]
+    fn too_short() {
+        let pairs = vec![[0.0; 2]; f64s::WIDTH - 1];
+        <[f64s; 2]>::load_interleaved(&pairs);
+    }
+}
@@ -0,0 +1,800 @@
+use ::std::cmp;
+use ::std::mem;
+use ::std::ops::{Deref, DerefMut};
+use ::std::marker::PhantomData;
+use ::vlist::{Cons, Nil};
+use ::packed::{Packed, PackedGats, Packable};
+
+/// A `ScalarSlice` that can be consumed in chunks of `Packed::WIDTH` scalars.
+///
+/// Implemented on `&[T]` for primitive `T: Packable`, and on VLists of these,
+/// which are iterated in lockstep.
+pub trait PackedSlice<'a>: Sized + Copy {
+    /// The vector type loaded from each chunk.
+    type Vector: Packed + Clone;
+
+    /// Number of scalars in the slice.
+    ///
+    /// For a VList, this is the length of the shortest slice.
+    fn len(&self) -> usize;
+
+    /// For a VList, whether all of the slices have the same length.
+    #[inline(always)]
+    fn lengths_match(&self) -> bool { true }
+
+    #[inline(always)]
+    fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Like `<[T]>::split_at`.
+    fn split_at(self, mid: usize) -> (Self, Self);
+
+    /// Read a single scalar. Panics if `idx` is out of bounds.
+    fn get(&self, idx: usize) -> <Self::Vector as Packed>::Scalar;
+
+    /// `Packed::load_unaligned_unchecked`.
+    ///
+    /// (this is here because the bound `Vector: PackedGats<'a, ScalarSlice = Self>`
+    ///  doesn't play nicely with the higher-ranked bound on `Packed`)
+    unsafe fn load_unchecked(self) -> Self::Vector;
+
+    /// Iterate over the slice in chunks of `Self::Vector`.
+    ///
+    /// By default only full chunks are produced; see `SimdIter::pad`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is a VList of slices with different lengths.
+    #[inline(always)]
+    fn simd_iter(self) -> SimdIter<'a, Self> {
+        assert!(self.lengths_match(), "simd_iter: slices have mismatched lengths");
+        SimdIter::new(self)
+    }
+
+    /// Like `simd_iter`, but a VList of slices of different lengths is permitted,
+    /// and only iterated up to the length of the shortest one.
+    /// (the excess of each slice appears in the remainder)
+    #[inline(always)]
+    fn simd_iter_truncated(self) -> SimdIter<'a, Self> {
+        SimdIter::new(self)
+    }
+
+    /// Apply `func` to each chunk and write the results into `out`, including
+    /// the final partial chunk.
+    ///
+    /// Lanes past the end of a partial chunk are filled with copies of the first scalar,
+    /// so `func` only ever sees values that were actually in the input.
+    ///
+    /// # Panics
+    ///
+    /// Panics unless all slices in `self` and `out` have the same length.
+    #[inline(always)]
+    fn simd_map_into<'b, Out, F>(self, out: Out, mut func: F)
+    where
+        Out: PackedSliceMut<'b>,
+        Out::Vector: Packed<Width = <Self::Vector as Packed>::Width>,
+        F: FnMut(Self::Vector) -> Out::Vector,
+    {
+        assert!(self.lengths_match(), "simd_map_into: slices have mismatched lengths");
+        assert!(out.lengths_match(), "simd_map_into: slices have mismatched lengths");
+        assert_eq!(self.len(), out.len(), "simd_map_into: output has wrong length");
+        if self.is_empty() {
+            return;
+        }
+
+        let pad = <Self::Vector as Packed>::splat(self.get(0));
+        let inputs = self.simd_iter().pad(pad);
+        let outputs = out.simd_iter_mut().pad(<Out::Vector as Packed>::default());
+        for (input, mut output) in inputs.zip(outputs) {
+            *output = func(input);
+        }
+    }
+}
+
+impl<'a, T> PackedSlice<'a> for &'a [T]
+where
+    T: Packable + Copy,
+    T::Vector: PackedGats<'a, ScalarSlice = &'a [T]>,
+{
+    type Vector = T::Vector;
+
+    #[inline(always)]
+    fn len(&self) -> usize { <[T]>::len(self) }
+
+    #[inline(always)]
+    fn split_at(self, mid: usize) -> (Self, Self) { <[T]>::split_at(self, mid) }
+
+    #[inline(always)]
+    fn get(&self, idx: usize) -> T { self[idx] }
+
+    #[inline(always)]
+    unsafe fn load_unchecked(self) -> T::Vector {
+        T::Vector::load_unaligned_unchecked(self)
+    }
+}
+
+// The base case is VList![S] rather than Nil, like the Packed impls.
+impl<'a, S> PackedSlice<'a> for Cons<S, Nil>
+where
+    S: PackedSlice<'a>,
+{
+    type Vector = Cons<S::Vector, Nil>;
+
+    #[inline(always)]
+    fn len(&self) -> usize { self.0.len() }
+
+    #[inline(always)]
+    fn lengths_match(&self) -> bool { self.0.lengths_match() }
+
+    #[inline(always)]
+    fn split_at(self, mid: usize) -> (Self, Self) {
+        let vlist_pat![s] = self;
+        let (s_left, s_right) = s.split_at(mid);
+        (vlist![s_left], vlist![s_right])
+    }
+
+    #[inline(always)]
+    fn get(&self, idx: usize) -> <Self::Vector as Packed>::Scalar {
+        vlist![self.0.get(idx)]
+    }
+
+    #[inline(always)]
+    unsafe fn load_unchecked(self) -> Self::Vector {
+        vlist![self.0.load_unchecked()]
+    }
+}
+
+impl<'a, S, Rest> PackedSlice<'a> for Cons<S, Rest>
+where
+    S: PackedSlice<'a>,
+    Rest: PackedSlice<'a>,
+    Cons<S::Vector, Rest::Vector>: Clone + Packed<
+        Scalar = Cons<<S::Vector as Packed>::Scalar, <Rest::Vector as Packed>::Scalar>,
+    >,
+{
+    type Vector = Cons<S::Vector, Rest::Vector>;
+
+    #[inline(always)]
+    fn len(&self) -> usize { cmp::min(self.0.len(), self.1.len()) }
+
+    #[inline(always)]
+    fn lengths_match(&self) -> bool {
+        self.0.lengths_match() && self.1.lengths_match() && self.0.len() == self.1.len()
+    }
+
+    #[inline(always)]
+    fn split_at(self, mid: usize) -> (Self, Self) {
+        let vlist_pat![s, ...rest] = self;
+        let (s_left, s_right) = s.split_at(mid);
+        let (rest_left, rest_right) = rest.split_at(mid);
+        (vlist![s_left, ...rest_left], vlist![s_right, ...rest_right])
+    }
+
+    #[inline(always)]
+    fn get(&self, idx: usize) -> <Self::Vector as Packed>::Scalar {
+        vlist![self.0.get(idx), ...self.1.get(idx)]
+    }
+
+    #[inline(always)]
+    unsafe fn load_unchecked(self) -> Self::Vector {
+        vlist![self.0.load_unchecked(), ...self.1.load_unchecked()]
+    }
+}
+
+/// The mutable counterpart to `PackedSlice`.
+///
+/// Implemented on `&mut [T]` for primitive `T: Packable`, and on VLists of these.
+pub trait PackedSliceMut<'a>: Sized + Default {
+    /// The vector type loaded from and stored to each chunk.
+    type Vector: Packed + Clone;
+
+    /// Number of scalars in the slice.
+    ///
+    /// For a VList, this is the length of the shortest slice.
+    fn len(&self) -> usize;
+
+    /// For a VList, whether all of the slices have the same length.
+    #[inline(always)]
+    fn lengths_match(&self) -> bool { true }
+
+    #[inline(always)]
+    fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Like `<[T]>::split_at_mut`.
+    fn split_at_mut(self, mid: usize) -> (Self, Self);
+
+    /// Read a single scalar. Panics if `idx` is out of bounds.
+    fn get(&self, idx: usize) -> <Self::Vector as Packed>::Scalar;
+
+    /// Write a single scalar. Panics if `idx` is out of bounds.
+    fn set(&mut self, idx: usize, value: <Self::Vector as Packed>::Scalar);
+
+    /// `Packed::load_unaligned_unchecked`.
+    unsafe fn load_unchecked(&self) -> Self::Vector;
+
+    /// `Packed::store_unaligned_unchecked`.
+    unsafe fn store_unchecked(&mut self, vector: Self::Vector);
+
+    /// Iterate over the slice in chunks of `Self::Vector`, which are written
+    /// back when the items are dropped.
+    ///
+    /// By default only full chunks are produced; see `SimdIterMut::pad`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is a VList of slices with different lengths.
+    #[inline(always)]
+    fn simd_iter_mut(self) -> SimdIterMut<'a, Self> {
+        assert!(self.lengths_match(), "simd_iter_mut: slices have mismatched lengths");
+        SimdIterMut::new(self)
+    }
+
+    /// Like `simd_iter_mut`, but a VList of slices of different lengths is permitted,
+    /// and only iterated up to the length of the shortest one.
+    #[inline(always)]
+    fn simd_iter_mut_truncated(self) -> SimdIterMut<'a, Self> {
+        SimdIterMut::new(self)
+    }
+
+    /// Replace every chunk `v` with `func(v)`, including the final partial chunk.
+    ///
+    /// Lanes past the end of a partial chunk are filled with copies of the first scalar,
+    /// as in `PackedSlice::simd_map_into`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this is a VList of slices with different lengths.
+    #[inline(always)]
+    fn simd_map_in_place<F>(self, mut func: F)
+    where F: FnMut(Self::Vector) -> Self::Vector,
+    {
+        if self.is_empty() {
+            return;
+        }
+
+        let pad = <Self::Vector as Packed>::splat(self.get(0));
+        for mut chunk in self.simd_iter_mut().pad(pad) {
+            *chunk = func((*chunk).clone());
+        }
+    }
+}
+
+impl<'a, T> PackedSliceMut<'a> for &'a mut [T]
+where
+    T: Packable + Copy,
+    T::Vector: for<'b> PackedGats<'b, ScalarSlice = &'b [T], ScalarSliceMut = &'b mut [T]>,
+{
+    type Vector = T::Vector;
+
+    #[inline(always)]
+    fn len(&self) -> usize { <[T]>::len(self) }
+
+    #[inline(always)]
+    fn split_at_mut(self, mid: usize) -> (Self, Self) { <[T]>::split_at_mut(self, mid) }
+
+    #[inline(always)]
+    fn get(&self, idx: usize) -> T { self[idx] }
+
+    #[inline(always)]
+    fn set(&mut self, idx: usize, value: T) { self[idx] = value; }
+
+    #[inline(always)]
+    unsafe fn load_unchecked(&self) -> T::Vector {
+        T::Vector::load_unaligned_unchecked(&self[..])
+    }
+
+    #[inline(always)]
+    unsafe fn store_unchecked(&mut self, vector: T::Vector) {
+        vector.store_unaligned_unchecked(&mut self[..])
+    }
+}
+
+impl<'a, S> PackedSliceMut<'a> for Cons<S, Nil>
+where
+    S: PackedSliceMut<'a>,
+{
+    type Vector = Cons<S::Vector, Nil>;
+
+    #[inline(always)]
+    fn len(&self) -> usize { self.0.len() }
+
+    #[inline(always)]
+    fn lengths_match(&self) -> bool { self.0.lengths_match() }
+
+    #[inline(always)]
+    fn split_at_mut(self, mid: usize) -> (Self, Self) {
+        let vlist_pat![s] = self;
+        let (s_left, s_right) = s.split_at_mut(mid);
+        (vlist![s_left], vlist![s_right])
+    }
+
+    #[inline(always)]
+    fn get(&self, idx: usize) -> <Self::Vector as Packed>::Scalar {
+        vlist![self.0.get(idx)]
+    }
+
+    #[inline(always)]
+    fn set(&mut self, idx: usize, value: <Self::Vector as Packed>::Scalar) {
+        let vlist_pat![value] = value;
+        self.0.set(idx, value);
+    }
+
+    #[inline(always)]
+    unsafe fn load_unchecked(&self) -> Self::Vector {
+        vlist![self.0.load_unchecked()]
+    }
+
+    #[inline(always)]
+    unsafe fn store_unchecked(&mut self, vector: Self::Vector) {
+        let vlist_pat![v] = vector;
+        self.0.store_unchecked(v);
+    }
+}
+
+impl<'a, S, Rest> PackedSliceMut<'a> for Cons<S, Rest>
+where
+    S: PackedSliceMut<'a>,
+    Rest: PackedSliceMut<'a>,
+    Cons<S::Vector, Rest::Vector>: Clone + Packed<
+        Scalar = Cons<<S::Vector as Packed>::Scalar, <Rest::Vector as Packed>::Scalar>,
+    >,
+{
+    type Vector = Cons<S::Vector, Rest::Vector>;
+
+    #[inline(always)]
+    fn len(&self) -> usize { cmp::min(self.0.len(), self.1.len()) }
+
+    #[inline(always)]
+    fn lengths_match(&self) -> bool {
+        self.0.lengths_match() && self.1.lengths_match() && self.0.len() == self.1.len()
+    }
+
+    #[inline(always)]
+    fn split_at_mut(self, mid: usize) -> (Self, Self) {
+        let vlist_pat![s, ...rest] = self;
+        let (s_left, s_right) = s.split_at_mut(mid);
+        let (rest_left, rest_right) = rest.split_at_mut(mid);
+        (vlist![s_left, ...rest_left], vlist![s_right, ...rest_right])
+    }
+
+    #[inline(always)]
+    fn get(&self, idx: usize) -> <Self::Vector as Packed>::Scalar {
+        vlist![self.0.get(idx), ...self.1.get(idx)]
+    }
+
+    #[inline(always)]
+    fn set(&mut self, idx: usize, value: <Self::Vector as Packed>::Scalar) {
+        let vlist_pat![v, ...rest] = value;
+        self.0.set(idx, v);
+        self.1.set(idx, rest);
+    }
+
+    #[inline(always)]
+    unsafe fn load_unchecked(&self) -> Self::Vector {
+        vlist![self.0.load_unchecked(), ...self.1.load_unchecked()]
+    }
+
+    #[inline(always)]
+    unsafe fn store_unchecked(&mut self, vector: Self::Vector) {
+        let vlist_pat![v, ...rest] = vector;
+        self.0.store_unchecked(v);
+        self.1.store_unchecked(rest);
+    }
+}
+
+/// What a `SimdIter` does with the final `len % WIDTH` scalars.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Tail<V> {
+    /// Only full vectors are produced.
+    /// The leftover scalars can be retrieved with `SimdIterator::remainder`.
+    Remainder,
+    /// The leftover scalars are produced as one final vector, whose missing lanes
+    /// are taken from the given vector.
+    Pad(V),
+}
+
+/// Iterator over `PackedSlice` chunks. Created by `PackedSlice::simd_iter`.
+#[derive(Debug, Clone)]
+pub struct SimdIter<'a, S: PackedSlice<'a>> {
+    data: S,
+    tail: Tail<S::Vector>,
+    _lifetime: PhantomData<&'a ()>,
+}
+
+impl<'a, S: PackedSlice<'a>> SimdIter<'a, S> {
+    #[inline(always)]
+    pub fn new(data: S) -> Self {
+        SimdIter { data, tail: Tail::Remainder, _lifetime: PhantomData }
+    }
+
+    /// Set the policy for the trailing partial chunk.
+    #[inline(always)]
+    pub fn tail(self, tail: Tail<S::Vector>) -> Self {
+        SimdIter { tail, ..self }
+    }
+
+    /// Shorthand for `.tail(Tail::Pad(default))`.
+    #[inline(always)]
+    pub fn pad(self, default: S::Vector) -> Self {
+        self.tail(Tail::Pad(default))
+    }
+
+    #[inline(always)]
+    fn width() -> usize { <S::Vector as Packed>::WIDTH }
+}
+
+impl<'a, S: PackedSlice<'a>> Iterator for SimdIter<'a, S> {
+    type Item = S::Vector;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<S::Vector> {
+        let width = Self::width();
+        let len = self.data.len();
+        if len >= width {
+            let (chunk, rest) = self.data.split_at(width);
+            self.data = rest;
+            // (safe because we just checked the length)
+            return Some(unsafe { chunk.load_unchecked() });
+        }
+
+        match self.tail {
+            Tail::Pad(ref default) if len > 0 => {
+                let mut vector = default.clone();
+                for i in 0..len {
+                    vector = vector.replace(i, self.data.get(i));
+                }
+                self.data = self.data.split_at(len).1;
+                Some(vector)
+            },
+            _ => None,
+        }
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.data.len();
+        let mut count = len / Self::width();
+        if let Tail::Pad(_) = self.tail {
+            if len % Self::width() != 0 {
+                count += 1;
+            }
+        }
+        (count, Some(count))
+    }
+}
+
+impl<'a, S: PackedSlice<'a>> ExactSizeIterator for SimdIter<'a, S> {}
+
+/// Iterator over `PackedSliceMut` chunks. Created by `PackedSliceMut::simd_iter_mut`.
+#[derive(Debug)]
+pub struct SimdIterMut<'a, S: PackedSliceMut<'a>> {
+    data: S,
+    tail: Tail<S::Vector>,
+    _lifetime: PhantomData<&'a mut ()>,
+}
+
+impl<'a, S: PackedSliceMut<'a>> SimdIterMut<'a, S> {
+    #[inline(always)]
+    pub fn new(data: S) -> Self {
+        SimdIterMut { data, tail: Tail::Remainder, _lifetime: PhantomData }
+    }
+
+    /// Set the policy for the trailing partial chunk.
+    ///
+    /// Under `Tail::Pad`, the padding lanes are discarded on write-back.
+    #[inline(always)]
+    pub fn tail(self, tail: Tail<S::Vector>) -> Self {
+        SimdIterMut { tail, ..self }
+    }
+
+    /// Shorthand for `.tail(Tail::Pad(default))`.
+    #[inline(always)]
+    pub fn pad(self, default: S::Vector) -> Self {
+        self.tail(Tail::Pad(default))
+    }
+
+    /// Give up the iterator, returning the scalars that would not be produced
+    /// as part of a vector.
+    #[inline(always)]
+    pub fn into_remainder(self) -> S {
+        let len = self.data.len();
+        self.data.split_at_mut(len - len % Self::width()).1
+    }
+
+    #[inline(always)]
+    fn width() -> usize { <S::Vector as Packed>::WIDTH }
+}
+
+impl<'a, S: PackedSliceMut<'a>> Iterator for SimdIterMut<'a, S> {
+    type Item = PackedMut<'a, S>;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let width = Self::width();
+        let len = self.data.len();
+        if len >= width {
+            let data = mem::replace(&mut self.data, S::default());
+            let (chunk, rest) = data.split_at_mut(width);
+            self.data = rest;
+            // (safe because we just checked the length)
+            let vector = unsafe { chunk.load_unchecked() };
+            return Some(PackedMut::new(vector, chunk));
+        }
+
+        match self.tail {
+            Tail::Pad(ref default) if len > 0 => {
+                let mut vector = default.clone();
+                for i in 0..len {
+                    vector = vector.replace(i, self.data.get(i));
+                }
+                let chunk = mem::replace(&mut self.data, S::default());
+                Some(PackedMut::new(vector, chunk))
+            },
+            _ => None,
+        }
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.data.len();
+        let mut count = len / Self::width();
+        if let Tail::Pad(_) = self.tail {
+            if len % Self::width() != 0 {
+                count += 1;
+            }
+        }
+        (count, Some(count))
+    }
+}
+
+impl<'a, S: PackedSliceMut<'a>> ExactSizeIterator for SimdIterMut<'a, S> {}
+
+/// A vector loaded from a chunk of a `PackedSliceMut`, which is written back on drop.
+///
+/// If the chunk is shorter than `WIDTH`, only the lanes that fit are written.
+#[derive(Debug)]
+pub struct PackedMut<'a, S: PackedSliceMut<'a>> {
+    vector: S::Vector,
+    dest: S,
+    _lifetime: PhantomData<&'a mut ()>,
+}
+
+impl<'a, S: PackedSliceMut<'a>> PackedMut<'a, S> {
+    #[inline(always)]
+    fn new(vector: S::Vector, dest: S) -> Self {
+        PackedMut { vector, dest, _lifetime: PhantomData }
+    }
+}
+
+impl<'a, S: PackedSliceMut<'a>> Deref for PackedMut<'a, S> {
+    type Target = S::Vector;
+
+    #[inline(always)]
+    fn deref(&self) -> &S::Vector { &self.vector }
+}
+
+impl<'a, S: PackedSliceMut<'a>> DerefMut for PackedMut<'a, S> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut S::Vector { &mut self.vector }
+}
+
+impl<'a, S: PackedSliceMut<'a>> Drop for PackedMut<'a, S> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        let len = self.dest.len();
+        if len >= <S::Vector as Packed>::WIDTH {
+            // (safe because we just checked the length)
+            unsafe { self.dest.store_unchecked(self.vector.clone()) };
+        } else {
+            for i in 0..len {
+                self.dest.set(i, self.vector.extract(i));
+            }
+        }
+    }
+}
+
+/// Adapters shared by the iterators in this module.
+pub trait SimdIterator: Iterator + Sized {
+    /// The `ScalarSlice` type left over under `Tail::Remainder`.
+    type Remainder;
+
+    /// The scalars that will not be produced as part of a vector.
+    ///
+    /// Under `Tail::Pad`, this is empty once the iterator is exhausted.
+    fn remainder(&self) -> Self::Remainder;
+
+    /// Lazily apply a function to each vector.
+    #[inline(always)]
+    fn simd_map<V, F>(self, func: F) -> SimdMap<Self, F>
+    where F: FnMut(Self::Item) -> V,
+    {
+        SimdMap { iter: self, func }
+    }
+
+    /// Fold all vectors into an accumulator (which is typically itself a vector).
+    #[inline(always)]
+    fn simd_reduce<A, F>(self, init: A, func: F) -> A
+    where F: FnMut(A, Self::Item) -> A,
+    {
+        self.fold(init, func)
+    }
+
+    #[inline(always)]
+    fn simd_for_each<F>(self, func: F)
+    where F: FnMut(Self::Item),
+    {
+        self.for_each(func)
+    }
+}
+
+impl<'a, S: PackedSlice<'a>> SimdIterator for SimdIter<'a, S> {
+    type Remainder = S;
+
+    #[inline(always)]
+    fn remainder(&self) -> S {
+        let len = self.data.len();
+        self.data.split_at(len - len % Self::width()).1
+    }
+}
+
+// so that `iter.by_ref().simd_for_each(...)` leaves the remainder accessible
+impl<'b, I: SimdIterator> SimdIterator for &'b mut I {
+    type Remainder = I::Remainder;
+
+    #[inline(always)]
+    fn remainder(&self) -> Self::Remainder { (**self).remainder() }
+}
+
+/// Created by `SimdIterator::simd_map`.
+#[derive(Debug, Clone)]
+pub struct SimdMap<I, F> {
+    iter: I,
+    func: F,
+}
+
+impl<I, F, V> Iterator for SimdMap<I, F>
+where
+    I: Iterator,
+    F: FnMut(I::Item) -> V,
+{
+    type Item = V;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<V> {
+        self.iter.next().map(&mut self.func)
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, F, V> ExactSizeIterator for SimdMap<I, F>
+where
+    I: ExactSizeIterator,
+    F: FnMut(I::Item) -> V,
+{}
+
+impl<I, F, V> SimdIterator for SimdMap<I, F>
+where
+    I: SimdIterator,
+    F: FnMut(I::Item) -> V,
+{
+    type Remainder = I::Remainder;
+
+    #[inline(always)]
+    fn remainder(&self) -> Self::Remainder { self.iter.remainder() }
+}
+
+//--------------------------------------------------------------------------------
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::faster::f64s;
+
+    #[test]
+    fn remainder() {
+        let xs: Vec<f64> = (0..2 * f64s::WIDTH + 1).map(|x| x as f64).collect();
+
+        let mut iter = xs.simd_iter();
+        assert_eq!(iter.len(), 2);
+        let total = iter.by_ref().simd_reduce(f64s(0.0), |acc, v| acc + v);
+        assert_eq!(iter.remainder(), &xs[2 * f64s::WIDTH..]);
+
+        let total = total.scalar_reduce(0.0, |a, b| a + b) + iter.remainder()[0];
+        assert_eq!(total, xs.iter().sum());
+    }
+
+    #[test]
+    fn zipped() {
+        let n = 3 * f64s::WIDTH + 1;
+        let xs: Vec<f64> = (0..n).map(|x| x as f64).collect();
+        let ys: Vec<f64> = (0..n).map(|x| 2.0 * x as f64).collect();
+        let zs: Vec<f64> = (0..n).map(|x| 3.0 * x as f64).collect();
+
+        let mut iter = vlist![&xs[..], &ys[..], &zs[..]].simd_iter();
+        let acc = iter.by_ref().simd_reduce(f64s(0.0), |acc, vlist_pat![x, y, z]| acc + x + y - z);
+        assert_eq!(acc, f64s(0.0));
+
+        let vlist_pat![x, y, z] = iter.remainder();
+        assert_eq!((x.len(), y.len(), z.len()), (1, 1, 1));
+    }
+
+    #[test]
+    fn map_into() {
+        let n = 2 * f64s::WIDTH + 1;
+        let xs: Vec<f64> = (0..n).map(|x| x as f64).collect();
+        let ys: Vec<f64> = (0..n).map(|x| 2.0 * x as f64).collect();
+        let mut sums = vec![0.0; n];
+        let mut diffs = vec![0.0; n];
+
+        vlist![&xs[..], &ys[..]].simd_map_into(
+            vlist![&mut sums[..], &mut diffs[..]],
+            |vlist_pat![x, y]| vlist![x + y, y - x],
+        );
+        assert_eq!(sums, (0..n).map(|x| 3.0 * x as f64).collect::<Vec<_>>());
+        assert_eq!(diffs, xs);
+    }
+
+    #[test]
+    fn map_in_place() {
+        let n = 2 * f64s::WIDTH + 1;
+        let mut re: Vec<f64> = (0..n).map(|x| x as f64).collect();
+        let mut im = vec![1.0; n];
+
+        // multiply by i
+        vlist![&mut re[..], &mut im[..]].simd_map_in_place(|vlist_pat![re, im]: VList![f64s, f64s]| {
+            vlist![-im, re]
+        });
+        assert_eq!(re, vec![-1.0; n]);
+        assert_eq!(im, (0..n).map(|x| x as f64).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn iter_mut_remainder() {
+        let mut xs = vec![1.0; f64s::WIDTH + 1];
+        {
+            let mut iter = xs.simd_iter_mut();
+            for mut v in iter.by_ref() {
+                *v = *v * f64s(3.0);
+            }
+            iter.into_remainder()[0] = 2.0;
+        }
+        assert_eq!(&xs[..f64s::WIDTH], &vec![3.0; f64s::WIDTH][..]);
+        assert_eq!(xs[f64s::WIDTH], 2.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "mismatched lengths")]
+    fn zipped_mismatch() {
+        let xs = vec![0.0f64; 9];
+        let ys = vec![0.0f64; 10];
+        vlist![&xs[..], &ys[..]].simd_iter();
+    }
+
+    #[test]
+    fn zipped_truncated() {
+        let xs = vec![1.0f64; f64s::WIDTH];
+        let ys = vec![2.0f64; f64s::WIDTH + 1];
+        let mut iter = vlist![&xs[..], &ys[..]].simd_iter_truncated().pad(vlist![f64s(0.0), f64s(0.0)]);
+        assert_eq!(iter.next(), Some(vlist![f64s(1.0), f64s(2.0)]));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.remainder(), vlist![&xs[..0], &ys[f64s::WIDTH..]]);
+    }
+
+    #[test]
+    fn pad() {
+        let xs: Vec<f64> = (0..f64s::WIDTH + 1).map(|x| x as f64).collect();
+
+        let mut out = vec![];
+        xs.simd_iter()
+            .pad(f64s(-1.0))
+            .simd_map(|v| v * f64s(2.0))
+            .simd_for_each(|v| out.push(v));
+
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[1].extract(0), 2.0 * f64s::WIDTH as f64);
+        assert_eq!(out[1].extract(1), -2.0);
+    }
+}
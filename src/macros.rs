@@ -34,3 +34,197 @@ macro_rules! VList {
         $crate::Cons<$A, VList![$($tok)*]>
     };
 }
+
+/// Implements `CustomPacked` for an existing struct, without a proc-macro.
+///
+/// This is the `macro_rules!` counterpart to `#[derive(CustomPacked)]`.
+/// List the vector struct's fields (in order) and name the scalar struct,
+/// which must have fields of the same names:
+///
+/// ```ignore
+/// struct Complex<V> { real: V, imag: V }
+///
+/// custom_packed! {
+///     impl<V> Complex<V> { real: V, imag: V } => Complex<V::Scalar>
+/// }
+///
+/// // tuple structs use the field indices as names
+/// struct TaggedVec(Complex<f32s>, f32s);
+/// struct Tagged<T>(Complex<T>, T);
+///
+/// custom_packed! {
+///     impl TaggedVec { 0: Complex<f32s>, 1: f32s } => Tagged<f32>
+/// }
+/// ```
+///
+/// The scalar struct may be named by a path (`=> ::num::Complex<V::Scalar>`).
+///
+/// Every type parameter is given a `Packed` bound.  If the fields have different
+/// type parameters, the bounds that make their widths agree go in a trailing `where`.
+///
+/// A `macro_rules!` macro can't see the struct definition, so the field list is restated
+/// here and must be kept in sync by hand.  Leaving out a field or getting its type wrong
+/// is a compile error, but nothing checks the order: the `BaseVector` (and so everything
+/// that goes through it, like `as_packed_mut`) follows the order written here.
+#[macro_export]
+macro_rules! custom_packed {
+    (
+        impl $(<$($P:ident),*>)* $Name:ident $(<$($Arg:ty),*>)* {
+            $($field:tt : $Field:ty),+ $(,)*
+        } => :: $($Scalar:ident)::+ $(<$($ScalarArg:ty),*>)*
+        $(where $($bounds:tt)*)*
+    ) => {
+        custom_packed! {
+            @impl [$($($P),*)*] $Name [$($($Arg),*)*] { $($field : $Field),+ }
+            => [:: $($Scalar)::+] [$($($ScalarArg),*)*]
+            where $($($bounds)*)*
+        }
+    };
+    (
+        impl $(<$($P:ident),*>)* $Name:ident $(<$($Arg:ty),*>)* {
+            $($field:tt : $Field:ty),+ $(,)*
+        } => $($Scalar:ident)::+ $(<$($ScalarArg:ty),*>)*
+        $(where $($bounds:tt)*)*
+    ) => {
+        custom_packed! {
+            @impl [$($($P),*)*] $Name [$($($Arg),*)*] { $($field : $Field),+ }
+            => [$($Scalar)::+] [$($($ScalarArg),*)*]
+            where $($($bounds)*)*
+        }
+    };
+
+    (
+        @impl [$($P:ident),*] $Name:ident [$($Arg:ty),*] { $($field:tt : $Field:ty),+ }
+        => [$($Scalar:tt)+] [$($ScalarArg:ty),*]
+        where $($bounds:tt)*
+    ) => {
+        impl<$($P),*> $crate::packed::CustomPacked for $Name<$($Arg),*>
+        where
+            $($P: $crate::Packed,)*
+            $($bounds)*
+        {
+            type CustomScalar = $($Scalar)+<$($ScalarArg),*>;
+            type BaseScalar = VList![$(<$Field as $crate::Packed>::Scalar),+];
+            type BaseVector = VList![$($Field),+];
+
+            #[inline(always)]
+            fn vector_into_base(vector: Self) -> Self::BaseVector {
+                vlist![$(vector.$field),+]
+            }
+
+            #[inline(always)]
+            fn vector_from_base(vector: Self::BaseVector) -> Self {
+                custom_packed!(@from_base [$Name], vector; []; $($field),+)
+            }
+
+            #[inline(always)]
+            fn scalar_into_base(scalar: Self::CustomScalar) -> Self::BaseScalar {
+                vlist![$(scalar.$field),+]
+            }
+
+            #[inline(always)]
+            fn scalar_from_base(scalar: Self::BaseScalar) -> Self::CustomScalar {
+                custom_packed!(@from_base [$($Scalar)+], scalar; []; $($field),+)
+            }
+
+            #[inline(always)]
+            fn vector_as_base<'a>(vector: &'a Self) -> <Self::BaseVector as $crate::packed::PackedGats<'a>>::Ref {
+                vlist![$($crate::packed::PackedGats::as_packed_ref(&vector.$field)),+]
+            }
+
+            #[inline(always)]
+            fn vector_as_base_mut<'a>(vector: &'a mut Self) -> <Self::BaseVector as $crate::packed::PackedGats<'a>>::RefMut {
+                vlist![$($crate::packed::PackedGats::as_packed_mut(&mut vector.$field)),+]
+            }
+        }
+    };
+
+    // Builds `Name { a: base.0, b: (base.1).0, ... }`, moving each field out of the VList.
+    (@from_base [$($Name:tt)+], $base:expr; [$($done:tt)*];) => {
+        $($Name)+ { $($done)* }
+    };
+    (@from_base [$($Name:tt)+], $base:expr; [$($done:tt)*]; $field:tt $(, $rest:tt)*) => {
+        custom_packed!(@from_base [$($Name)+], ($base).1; [$($done)* $field: ($base).0,]; $($rest),*)
+    };
+}
+
+/// Implements `std::ops` traits element-wise for a `CustomPacked` type, through its `BaseVector`.
+///
+/// Only the listed operators are implemented, so that any with different semantics
+/// (like `Mul` on complex numbers) can be written by hand:
+///
+/// ```ignore
+/// custom_packed_ops! {
+///     impl<V> Complex<V>: Add, Sub, Neg
+/// }
+///
+/// impl<V: Packed + Copy + Add<Output=V> + Sub<Output=V> + Mul<Output=V>> Mul for Complex<V> { ... }
+/// ```
+///
+/// The binary operators take `Self` on both sides.  Available operators are
+/// `Add`, `Sub`, `Mul`, `Div`, `Rem`, `BitAnd`, `BitOr`, `BitXor`, `Shl`, `Shr`, `Neg` and `Not`.
+/// (`#[derive(CustomPacked)]` can do the same with `#[custom_packed(ops = "Add, Sub, Neg")]`)
+#[macro_export]
+macro_rules! custom_packed_ops {
+    (
+        impl $(<$($P:ident),*>)* $Name:ident $(<$($Arg:ty),*>)* : $($Op:ident),+ $(,)*
+    ) => {
+        custom_packed_ops!(@each [$($($P),*)*] [$Name $(<$($Arg),*>)*]; $($Op),+);
+    };
+
+    (@each $P:tt $Self:tt;) => {};
+    (@each $P:tt $Self:tt; $Op:ident $(, $rest:ident)*) => {
+        custom_packed_ops!(@op $P $Self; $Op);
+        custom_packed_ops!(@each $P $Self; $($rest),*);
+    };
+
+    (@op $P:tt $Self:tt; Add) => { custom_packed_ops!(@binop $P $Self; Add add); };
+    (@op $P:tt $Self:tt; Sub) => { custom_packed_ops!(@binop $P $Self; Sub sub); };
+    (@op $P:tt $Self:tt; Mul) => { custom_packed_ops!(@binop $P $Self; Mul mul); };
+    (@op $P:tt $Self:tt; Div) => { custom_packed_ops!(@binop $P $Self; Div div); };
+    (@op $P:tt $Self:tt; Rem) => { custom_packed_ops!(@binop $P $Self; Rem rem); };
+    (@op $P:tt $Self:tt; BitAnd) => { custom_packed_ops!(@binop $P $Self; BitAnd bitand); };
+    (@op $P:tt $Self:tt; BitOr) => { custom_packed_ops!(@binop $P $Self; BitOr bitor); };
+    (@op $P:tt $Self:tt; BitXor) => { custom_packed_ops!(@binop $P $Self; BitXor bitxor); };
+    (@op $P:tt $Self:tt; Shl) => { custom_packed_ops!(@binop $P $Self; Shl shl); };
+    (@op $P:tt $Self:tt; Shr) => { custom_packed_ops!(@binop $P $Self; Shr shr); };
+    (@op $P:tt $Self:tt; Neg) => { custom_packed_ops!(@unop $P $Self; Neg neg); };
+    (@op $P:tt $Self:tt; Not) => { custom_packed_ops!(@unop $P $Self; Not not); };
+
+    (@binop [$($P:ident),*] [$Self:ty]; $Add:ident $add:ident) => {
+        impl<$($P),*> ::std::ops::$Add for $Self
+        where
+            $Self: $crate::packed::CustomPacked,
+            <$Self as $crate::packed::CustomPacked>::BaseVector:
+                ::std::ops::$Add<Output = <$Self as $crate::packed::CustomPacked>::BaseVector>,
+        {
+            type Output = Self;
+
+            #[inline(always)]
+            fn $add(self, other: Self) -> Self {
+                <Self as $crate::packed::CustomPacked>::vector_from_base(::std::ops::$Add::$add(
+                    <Self as $crate::packed::CustomPacked>::vector_into_base(self),
+                    <Self as $crate::packed::CustomPacked>::vector_into_base(other),
+                ))
+            }
+        }
+    };
+
+    (@unop [$($P:ident),*] [$Self:ty]; $Neg:ident $neg:ident) => {
+        impl<$($P),*> ::std::ops::$Neg for $Self
+        where
+            $Self: $crate::packed::CustomPacked,
+            <$Self as $crate::packed::CustomPacked>::BaseVector:
+                ::std::ops::$Neg<Output = <$Self as $crate::packed::CustomPacked>::BaseVector>,
+        {
+            type Output = Self;
+
+            #[inline(always)]
+            fn $neg(self) -> Self {
+                <Self as $crate::packed::CustomPacked>::vector_from_base(::std::ops::$Neg::$neg(
+                    <Self as $crate::packed::CustomPacked>::vector_into_base(self),
+                ))
+            }
+        }
+    };
+}
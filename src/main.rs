@@ -1,17 +1,49 @@
 #![feature(stdsimd)]
+#![feature(platform_intrinsics)]
 
 extern crate faster;
+extern crate typenum;
+#[macro_use]
+extern crate zip_simd_derive;
 
 #[macro_use]
 mod macros;
+mod intrinsics;
 
-pub use self::vlist::{Cons, Nil};
+pub use self::vlist::{Cons, Nil, IntoTuple};
 mod vlist;
 
+pub mod typelevel;
+
 pub use self::packed::{Packed, Packable};
 pub mod packed;
 
-//mod iter;
+pub use self::mask::PackedMask;
+pub mod mask;
+
+pub use self::index::IndexVector;
+pub mod index;
+
+pub use self::reduce::PackedSum;
+pub mod reduce;
+
+pub use self::math::{PackedFloat, FieldwisePackedFloat};
+pub mod math;
+
+pub use self::iter::{PackedSlice, PackedSliceMut, SimdIterator};
+pub mod iter;
+
+pub use self::aligned::AlignedVec;
+pub mod aligned;
+
+pub use self::soa::SoaVec;
+pub mod soa;
+
+pub use self::interleave::PackedInterleaved;
+pub mod interleave;
+
+pub use self::complex::Complex;
+pub mod complex;
 
 fn main() {
     println!("Hello, world!");
@@ -0,0 +1,292 @@
+use ::std::mem;
+use ::std::any::TypeId;
+use ::std::ops::{BitAnd, BitOr, BitXor, Not};
+use ::vlist::{Cons, Nil};
+use ::packed::Packed;
+use ::typelevel::{TypeLevelUsize, U2, U4, U8, U16, U32, U64};
+use ::std::simd::{m8x2, m8x4, m8x8, m8x16, m8x32, m8x64};
+use ::std::simd::{m16x2, m16x4, m16x8, m16x16, m16x32};
+use ::std::simd::{m32x2, m32x4, m32x8, m32x16};
+use ::std::simd::{m64x2, m64x4, m64x8};
+
+/// The result of a lane-wise comparison of `Packed` types.
+///
+/// Implemented on:
+/// * the mask types in std (`m32x4`, ...)
+/// * VLists thereof, which are the masks of VLists and custom types (one mask per field)
+///
+/// For a VList of masks, `all`, `any` and `none` look at every lane of every field;
+/// to ask a question about each lane, reduce it to a single mask with `lanes_all`
+/// or `lanes_any`.
+pub trait PackedMask
+    : Sized + Copy
+    + BitAnd<Output = Self> + BitOr<Output = Self> + BitXor<Output = Self> + Not<Output = Self>
+{
+    type Width: TypeLevelUsize;
+    const WIDTH: usize = Self::Width::VALUE;
+
+    /// The primitive mask produced by `lanes_all` and `lanes_any`.
+    type Lanes: PackedMask<Width = Self::Width, Lanes = Self::Lanes> + 'static;
+
+    fn splat(value: bool) -> Self;
+
+    fn all(self) -> bool;
+    fn any(self) -> bool;
+
+    #[inline(always)]
+    fn none(self) -> bool { !self.any() }
+
+    /// A mask whose lane `i` is set if lane `i` is set in every field.
+    fn lanes_all(self) -> Self::Lanes;
+
+    /// A mask whose lane `i` is set if lane `i` is set in any field.
+    fn lanes_any(self) -> Self::Lanes;
+
+    /// Bit `i` is set if lane `i` is set (in every field, for a VList of masks).
+    fn to_bitmask(self) -> u64;
+
+    /// Sets lane `i` (of every field) if bit `i` is set.  Bits past `WIDTH` are ignored.
+    fn from_bitmask(bits: u64) -> Self;
+
+    /// `Packed::select`; takes each lane of each field from `a` where set, and from `b` elsewhere.
+    #[inline(always)]
+    fn select<V>(self, a: V, b: V) -> V
+    where V: Packed<Mask = Self>,
+    {
+        V::select(self, a, b)
+    }
+
+    /// `Packed::select_lanes`; takes whole elements from `a` where set, and from `b` elsewhere.
+    #[inline(always)]
+    fn select_lanes<V>(self, a: V, b: V) -> V
+    where
+        V: Packed,
+        V::Mask: PackedMask<Lanes = Self>,
+    {
+        V::select_lanes(self, a, b)
+    }
+}
+
+macro_rules! impl_primitive_packed_mask {
+    ($($Mask:ident: $Width:ty,)*) => {$(
+        impl PackedMask for $Mask {
+            type Width = $Width;
+            type Lanes = Self;
+
+            #[inline(always)]
+            fn splat(value: bool) -> Self { <$Mask>::splat(value) }
+
+            #[inline(always)]
+            fn all(self) -> bool { <$Mask>::all(self) }
+
+            #[inline(always)]
+            fn any(self) -> bool { <$Mask>::any(self) }
+
+            #[inline(always)]
+            fn none(self) -> bool { <$Mask>::none(self) }
+
+            #[inline(always)]
+            fn lanes_all(self) -> Self { self }
+
+            #[inline(always)]
+            fn lanes_any(self) -> Self { self }
+
+            #[inline(always)]
+            fn to_bitmask(self) -> u64 {
+                let mut bits = 0;
+                for i in 0..Self::WIDTH {
+                    bits |= (unsafe { self.extract_unchecked(i) } as u64) << i;
+                }
+                bits
+            }
+
+            #[inline(always)]
+            fn from_bitmask(bits: u64) -> Self {
+                let mut mask = <$Mask>::splat(false);
+                for i in 0..Self::WIDTH {
+                    mask = unsafe { mask.replace_unchecked(i, bits & (1 << i) != 0) };
+                }
+                mask
+            }
+        }
+    )*};
+}
+
+impl_primitive_packed_mask!{
+    m8x2: U2,
+    m8x4: U4,
+    m8x8: U8,
+    m8x16: U16,
+    m8x32: U32,
+    m8x64: U64,
+    m16x2: U2,
+    m16x4: U4,
+    m16x8: U8,
+    m16x16: U16,
+    m16x32: U32,
+    m32x2: U2,
+    m32x4: U4,
+    m32x8: U8,
+    m32x16: U16,
+    m64x2: U2,
+    m64x4: U4,
+    m64x8: U8,
+}
+
+impl<M> PackedMask for Cons<M, Nil>
+where
+    M: PackedMask,
+{
+    type Width = M::Width;
+    type Lanes = M::Lanes;
+
+    #[inline(always)]
+    fn splat(value: bool) -> Self { vlist![M::splat(value)] }
+
+    #[inline(always)]
+    fn all(self) -> bool { self.0.all() }
+
+    #[inline(always)]
+    fn any(self) -> bool { self.0.any() }
+
+    #[inline(always)]
+    fn lanes_all(self) -> Self::Lanes { self.0.lanes_all() }
+
+    #[inline(always)]
+    fn lanes_any(self) -> Self::Lanes { self.0.lanes_any() }
+
+    #[inline(always)]
+    fn to_bitmask(self) -> u64 { self.0.to_bitmask() }
+
+    #[inline(always)]
+    fn from_bitmask(bits: u64) -> Self { vlist![M::from_bitmask(bits)] }
+}
+
+// (the fields may be masks of different lane sizes, so they are combined through a bitmask)
+impl<M, Rest> PackedMask for Cons<M, Rest>
+where
+    M: PackedMask,
+    Rest: PackedMask<Width = M::Width>,
+{
+    type Width = M::Width;
+    type Lanes = M::Lanes;
+
+    #[inline(always)]
+    fn splat(value: bool) -> Self {
+        vlist![M::splat(value), ...Rest::splat(value)]
+    }
+
+    #[inline(always)]
+    fn all(self) -> bool { self.0.all() && self.1.all() }
+
+    #[inline(always)]
+    fn any(self) -> bool { self.0.any() || self.1.any() }
+
+    #[inline(always)]
+    fn lanes_all(self) -> Self::Lanes {
+        self.0.lanes_all() & M::Lanes::from_bitmask(self.1.to_bitmask())
+    }
+
+    #[inline(always)]
+    fn lanes_any(self) -> Self::Lanes {
+        self.0.lanes_any() | M::Lanes::from_bitmask(self.1.lanes_any().to_bitmask())
+    }
+
+    #[inline(always)]
+    fn to_bitmask(self) -> u64 {
+        self.0.to_bitmask() & self.1.to_bitmask()
+    }
+
+    #[inline(always)]
+    fn from_bitmask(bits: u64) -> Self {
+        vlist![M::from_bitmask(bits), ...Rest::from_bitmask(bits)]
+    }
+}
+
+/// Converts a primitive mask into another of the same width, which may have a different
+/// lane size (e.g. `m64x4` into `m32x4`).
+///
+/// (when they are the same type, as for the fields of most VLists, this compiles to nothing)
+#[inline(always)]
+pub(crate) fn cast_lanes<M, N>(mask: M) -> N
+where
+    M: PackedMask + 'static,
+    N: PackedMask<Width = M::Width> + 'static,
+{
+    if TypeId::of::<M>() == TypeId::of::<N>() {
+        unsafe { mem::transmute_copy(&mask) }
+    } else {
+        N::from_bitmask(mask.to_bitmask())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::faster::{f64s, i64s};
+    use ::complex::Complex;
+    use ::faster::vecs::{f32x4, f64x4};
+
+    #[test]
+    fn primitive() {
+        let xs = f64s::splat(1.0).replace(1, 3.0);
+        let mask = Packed::lt(xs, f64s::splat(2.0));
+        assert!(mask.any() && !mask.all() && !mask.none());
+        assert_eq!(mask.to_bitmask(), ((1 << f64s::WIDTH) - 1) & !0b10);
+        assert_eq!(<f64s as Packed>::Mask::from_bitmask(mask.to_bitmask()), mask);
+        assert_eq!((!mask).to_bitmask(), 0b10);
+        assert!((mask ^ mask).none());
+    }
+
+    #[test]
+    fn vlist() {
+        type V = <VList![f32, f64] as ::packed::Packable>::Vector;
+        let a = V::splat(vlist![1.0, 1.0]).replace(0, vlist![0.0, 1.0]).replace(1, vlist![1.0, 0.0]);
+        let b = V::splat(vlist![1.0, 1.0]);
+
+        let mask = a.eq(b);
+        assert!(mask.any() && !mask.all());
+        let both = mask.lanes_all();
+        assert!(!both.extract(0) && !both.extract(1) && both.extract(2));
+        let either = mask.lanes_any();
+        assert!(either.extract(0) && either.extract(1) && either.extract(2));
+        assert_eq!(mask.to_bitmask(), both.to_bitmask());
+        assert!(a.le(b).all() && a.gt(b).none());
+    }
+
+    #[test]
+    fn custom() {
+        let z = Complex::<f64s>::splat(Complex { real: 1.0, imag: 2.0 });
+        let w = z.replace(2, Complex { real: 1.0, imag: 0.0 });
+        let vlist_pat![real, imag] = z.ne(w);
+        assert!(real.none());
+        assert_eq!(imag.to_bitmask(), 0b100);
+        assert_eq!(z.ne(w).lanes_any().to_bitmask(), 0b100);
+
+        // choose whole complex numbers by their real parts
+        let u = Complex { real: f64s::splat(0.0).replace(1, 2.0), imag: f64s::splat(5.0) };
+        let picked = u.real.gt(z.real).select_lanes(u, z);
+        assert_eq!(picked.extract(0), Complex { real: 1.0, imag: 2.0 });
+        assert_eq!(picked.extract(1), Complex { real: 2.0, imag: 5.0 });
+
+        // or field by field
+        let maxes = PackedMask::select(u.ge(z), u, z);
+        assert_eq!(maxes.extract(0), Complex { real: 1.0, imag: 5.0 });
+        assert_eq!(maxes.extract(1), Complex { real: 2.0, imag: 5.0 });
+
+        let arrays = <[f64s; 2]>::splat([1.0, 2.0]);
+        let swapped = Packed::select(arrays.lt(<[f64s; 2]>::splat([2.0, 0.0])), <[f64s; 2]>::splat([9.0, 9.0]), arrays);
+        assert_eq!(swapped.extract(0), [9.0, 2.0]);
+        let mixed = <VList![f64s, i64s]>::splat(vlist![1.0, 1]);
+        let chosen = Packed::select_lanes(f64s::splat(0.0).lt(f64s::splat(1.0).replace(3, -1.0)), mixed, Packed::default());
+        assert_eq!(chosen.extract(3), vlist![0.0, 0]);
+        assert_eq!(chosen.extract(0), vlist![1.0, 1]);
+        // (with different lane sizes)
+        let mixed = <VList![f64x4, f32x4]>::splat(vlist![1.0, 1.0]);
+        let chosen = Packed::select_lanes(m64x4::splat(false).replace(2, true), mixed, Packed::default());
+        assert_eq!(chosen.extract(2), vlist![1.0, 1.0]);
+        assert_eq!(chosen.extract(1), vlist![0.0, 0.0]);
+
+        assert_eq!(arrays.ge(<[f64s; 2]>::splat([1.0, 3.0])).lanes_all().to_bitmask(), 0);
+    }
+}
@@ -0,0 +1,1108 @@
+use ::std::mem;
+use ::std::f64::consts::{PI, FRAC_PI_2, FRAC_PI_4, FRAC_2_PI, LOG2_E};
+use ::std::ops::{Add, Sub, Mul, Div, Neg, BitAnd, BitOr, Not, Shl, Shr};
+use ::vlist::{Cons, Nil};
+use ::packed::{Packed, CustomPacked};
+use ::mask::PackedMask;
+use ::faster::vecs::{f32x4, f32x8, f32x16, f64x2, f64x4, f64x8};
+use ::faster::vecs::{i32x4, i32x8, i32x16, i64x2, i64x4, i64x8};
+use ::std::simd::{f32x2, i32x2};
+
+/// Elementary functions on vectors of floats, applied to each lane.
+///
+/// Implemented on:
+/// * the `f32` and `f64` vectors in std, with the approximations below
+/// * VLists whose fields all implement it (field by field)
+/// * custom types that opt into the same with `FieldwisePackedFloat`
+///
+/// The accuracy of each method is given in ULPs ("units in the last place") away from
+/// the corresponding function in std, for the whole range of inputs unless noted
+/// otherwise.  Special values (`0`, `-0`, infinities and NaNs) give the same results as
+/// in std, and subnormal inputs and results are supported.
+pub trait PackedFloat: Packed {
+    /// The square root; exact, as it uses the hardware instruction.
+    fn sqrt(self) -> Self;
+
+    /// The reciprocal square root, `1 / x.sqrt()`; within 2 ULP.
+    fn rsqrt(self) -> Self;
+
+    /// `e^x`; within 1 ULP.
+    fn exp(self) -> Self;
+
+    /// The natural logarithm; within 1 ULP.
+    fn ln(self) -> Self;
+
+    /// The base 2 logarithm; within 1 ULP.  Exact for powers of two.
+    fn log2(self) -> Self;
+
+    /// The sine; within 1 ULP for `|x| <= 1e5` (`8192` for `f32`).
+    ///
+    /// (lanes beyond that make the whole vector fall back to std, lane by lane)
+    fn sin(self) -> Self;
+
+    /// The cosine; same accuracy as `sin`.
+    fn cos(self) -> Self;
+
+    /// The tangent; within 2 ULP, over the same range as `sin`.
+    fn tan(self) -> Self;
+
+    /// The four-quadrant arctangent of `self / x`, like `f64::atan2`; within 2 ULP.
+    fn atan2(self, x: Self) -> Self;
+
+    /// `self` raised to the power `y`, like `f64::powf`; within 2 ULP.
+    fn pow(self, y: Self) -> Self;
+
+    /// The hyperbolic tangent; within 2 ULP.
+    fn tanh(self) -> Self;
+}
+
+//--------------------------------------------------------------------------------
+// The approximations are written once, for any vector type implementing `FloatVector`,
+// using only arithmetic, comparisons and integer operations on the bits.  (everything
+// relies on IEEE rounding of each operation, so these must never be compiled with
+// contractions or other "fast math")
+//
+// Each one reduces its argument to a small range where a polynomial (Taylor series,
+// truncated well past the point where the terms stop mattering) is accurate, using
+// Cody-Waite style constants split into exact high parts and small low parts.
+
+// The parameters of the approximations for one precision.
+//
+// (floats are given as f64, and converted to the lane type on use)
+struct Constants {
+    mantissa_bits: i64,
+    exponent_bias: i64,
+    // everything at least this large is an integer; adding and subtracting 1.5 times
+    // this rounds to an integer (and leaves it in the low bits)
+    two_pow_mantissa: f64,
+    min_positive: f64,
+    // tiny or huge inputs are scaled by this (an even power of two) before taking roots
+    // and logs
+    scale: f64,
+    scale_sqrt: f64,
+    scale_exponent: f64,
+    huge: f64,
+    // for splitting a float into two halves that can be multiplied exactly
+    split: f64,
+
+    rsqrt_magic: i64,
+    newton_steps: usize,
+
+    ln2: [f64; 2],
+    log2_e: [f64; 2],
+    two_thirds: [f64; 2],
+    // the coefficients of `x^2 / 2! + x^3 / 3! + ...`, divided by `x^2`
+    expm1: &'static [f64],
+    // where `exp` is certain to have overflowed or underflowed
+    exp_limit: f64,
+    // the coefficients of `2 s^5 / 5 + 2 s^7 / 7 + ...` (in `ln(1 + f) = 2 atanh(s)`),
+    // divided by `s^5`, as a polynomial in `s^2`
+    ln: &'static [f64],
+    // where `tanh` rounds to 1
+    tanh_limit: f64,
+
+    frac_2_pi: f64,
+    // pi/2 in parts small enough that multiplying them by the quadrant is exact
+    // (except for the last)
+    pi_2: &'static [f64],
+    trig_limit: f64,
+    // the coefficients of `sin` and `cos` past the first few terms, as polynomials in `x^2`
+    sin: &'static [f64],
+    cos: &'static [f64],
+
+    pi: [f64; 2],
+    frac_pi_2: [f64; 2],
+    frac_pi_4: [f64; 2],
+    tan_pi_8: f64,
+    tan_3pi_8: f64,
+    // the coefficients of `atan` after the first term, as a polynomial in `x^2`
+    atan: &'static [f64],
+
+    // where `pow` is certain to have overflowed or underflowed, unless the base is 1
+    pow_limit: f64,
+}
+
+const F64: Constants = Constants {
+    mantissa_bits: 52,
+    exponent_bias: 1023,
+    two_pow_mantissa: 4503599627370496.0,
+    min_positive: 2.2250738585072014e-308,
+    scale: 18014398509481984.0, // 2^54
+    scale_sqrt: 134217728.0,
+    scale_exponent: 54.0,
+    huge: 1.0715086071862673e301, // 2^1000
+    split: 134217729.0, // 2^27 + 1
+
+    rsqrt_magic: 0x5FE6_EB50_C7B5_37A9,
+    newton_steps: 4,
+
+    ln2: [0.6931471803691238, 1.9082149292705877e-10],
+    log2_e: [LOG2_E, 2.0355273740931033e-17],
+    two_thirds: [0.6666666666666666, 3.700743415417188e-17],
+    expm1: &[
+        1.0 / 2.0, 1.0 / 6.0, 1.0 / 24.0, 1.0 / 120.0, 1.0 / 720.0, 1.0 / 5040.0,
+        1.0 / 40320.0, 1.0 / 362880.0, 1.0 / 3628800.0, 1.0 / 39916800.0,
+        1.0 / 479001600.0, 1.0 / 6227020800.0,
+    ],
+    exp_limit: 750.0,
+    ln: &[
+        2.0 / 5.0, 2.0 / 7.0, 2.0 / 9.0, 2.0 / 11.0, 2.0 / 13.0, 2.0 / 15.0,
+        2.0 / 17.0, 2.0 / 19.0, 2.0 / 21.0, 2.0 / 23.0,
+    ],
+    tanh_limit: 20.0,
+
+    frac_2_pi: FRAC_2_PI,
+    pi_2: &[1.5707963267341256, 6.077100506303966e-11, 2.0222662487111665e-21, 8.4784276603689e-32],
+    trig_limit: 1e5,
+    sin: &[
+        -1.0 / 6.0, 1.0 / 120.0, -1.0 / 5040.0, 1.0 / 362880.0, -1.0 / 39916800.0,
+        1.0 / 6227020800.0, -1.0 / 1307674368000.0, 1.0 / 355687428096000.0,
+    ],
+    cos: &[
+        1.0 / 24.0, -1.0 / 720.0, 1.0 / 40320.0, -1.0 / 3628800.0, 1.0 / 479001600.0,
+        -1.0 / 87178291200.0, 1.0 / 20922789888000.0, -1.0 / 6402373705728000.0,
+    ],
+
+    pi: [PI, 1.2246467991473532e-16],
+    frac_pi_2: [FRAC_PI_2, 6.123233995736766e-17],
+    frac_pi_4: [FRAC_PI_4, 3.061616997868383e-17],
+    tan_pi_8: 0.41421356237309503,
+    tan_3pi_8: 2.414213562373095,
+    atan: &[
+        -1.0 / 3.0, 1.0 / 5.0, -1.0 / 7.0, 1.0 / 9.0, -1.0 / 11.0, 1.0 / 13.0, -1.0 / 15.0,
+        1.0 / 17.0, -1.0 / 19.0, 1.0 / 21.0, -1.0 / 23.0, 1.0 / 25.0, -1.0 / 27.0,
+        1.0 / 29.0, -1.0 / 31.0, 1.0 / 33.0, -1.0 / 35.0, 1.0 / 37.0, -1.0 / 39.0,
+        1.0 / 41.0, -1.0 / 43.0,
+    ],
+
+    pow_limit: 1.1805916207174113e21, // 2^70
+};
+
+const F32: Constants = Constants {
+    mantissa_bits: 23,
+    exponent_bias: 127,
+    two_pow_mantissa: 8388608.0,
+    min_positive: 1.1754943508222875e-38,
+    scale: 16777216.0, // 2^24
+    scale_sqrt: 4096.0,
+    scale_exponent: 24.0,
+    huge: 1.329227995784916e36, // 2^120
+    split: 4097.0, // 2^12 + 1
+
+    rsqrt_magic: 0x5F37_5A86,
+    newton_steps: 3,
+
+    ln2: [0.693359375, -2.1219444005469057e-4],
+    log2_e: [LOG2_E as f32 as f64, 1.92596298909109e-08],
+    two_thirds: [0.6666666865348816, -1.9868214962137642e-08],
+    expm1: &[1.0 / 2.0, 1.0 / 6.0, 1.0 / 24.0, 1.0 / 120.0, 1.0 / 720.0, 1.0 / 5040.0],
+    exp_limit: 110.0,
+    ln: &[2.0 / 5.0, 2.0 / 7.0, 2.0 / 9.0, 2.0 / 11.0, 2.0 / 13.0],
+    tanh_limit: 10.0,
+
+    frac_2_pi: FRAC_2_PI,
+    pi_2: &[1.5703125, 4.837512969970703e-4, 7.549533620476723e-08, 2.5632829192545614e-12, 6.123234262925839e-17],
+    trig_limit: 8192.0,
+    sin: &[-1.0 / 6.0, 1.0 / 120.0, -1.0 / 5040.0, 1.0 / 362880.0, -1.0 / 39916800.0],
+    cos: &[1.0 / 24.0, -1.0 / 720.0, 1.0 / 40320.0, -1.0 / 3628800.0, 1.0 / 479001600.0],
+
+    pi: [PI as f32 as f64, -8.742278012618954e-08],
+    frac_pi_2: [FRAC_PI_2 as f32 as f64, -4.371139006309477e-08],
+    frac_pi_4: [FRAC_PI_4 as f32 as f64, -2.1855695031547384e-08],
+    tan_pi_8: 0.41421356237309503,
+    tan_3pi_8: 2.414213562373095,
+    atan: &[
+        -1.0 / 3.0, 1.0 / 5.0, -1.0 / 7.0, 1.0 / 9.0, -1.0 / 11.0, 1.0 / 13.0, -1.0 / 15.0,
+        1.0 / 17.0, -1.0 / 19.0,
+    ],
+
+    pow_limit: 1099511627776.0, // 2^40
+};
+
+// A primitive float vector, and an integer vector with the same lane size for its bits.
+trait FloatVector
+    : Packed + Copy
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Neg<Output = Self>
+{
+    type Bits
+        : Copy
+        + Add<Output = Self::Bits> + Sub<Output = Self::Bits>
+        + BitAnd<Output = Self::Bits> + BitOr<Output = Self::Bits> + Not<Output = Self::Bits>
+        + Shl<Self::Bits, Output = Self::Bits> + Shr<Self::Bits, Output = Self::Bits>;
+
+    const C: Constants;
+
+    fn constant(x: f64) -> Self;
+    fn int(x: i64) -> Self::Bits;
+
+    fn to_bits(self) -> Self::Bits;
+    fn from_bits(bits: Self::Bits) -> Self;
+
+    /// Lanes where `bits` is not zero.
+    fn nonzero(bits: Self::Bits) -> Self::Mask;
+}
+
+macro_rules! impl_float_vector {
+    ($($Consts:ident, $Scalar:ident, $Int:ident: [$($Vector:ident: $Bits:ident),*];)*) => {$($(
+        impl FloatVector for $Vector {
+            type Bits = $Bits;
+
+            const C: Constants = $Consts;
+
+            #[inline(always)]
+            fn constant(x: f64) -> Self { <$Vector>::splat(x as $Scalar) }
+
+            #[inline(always)]
+            fn int(x: i64) -> $Bits { <$Bits>::splat(x as $Int) }
+
+            #[inline(always)]
+            fn to_bits(self) -> $Bits { unsafe { mem::transmute(self) } }
+
+            #[inline(always)]
+            fn from_bits(bits: $Bits) -> Self { unsafe { mem::transmute(bits) } }
+
+            #[inline(always)]
+            fn nonzero(bits: $Bits) -> Self::Mask { bits.ne(<$Bits>::splat(0)) }
+        }
+    )*)*};
+}
+
+impl_float_vector!{
+    F32, f32, i32: [f32x2: i32x2, f32x4: i32x4, f32x8: i32x8, f32x16: i32x16];
+    F64, f64, i64: [f64x2: i64x2, f64x4: i64x4, f64x8: i64x8];
+}
+
+// `coeffs[0] + coeffs[1] x + coeffs[2] x^2 + ...`
+#[inline(always)]
+fn poly<V: FloatVector>(x: V, coeffs: &[f64]) -> V {
+    let (last, rest) = coeffs.split_last().expect("empty polynomial");
+    rest.iter().rev().fold(V::constant(*last), |acc, &c| acc * x + V::constant(c))
+}
+
+#[inline(always)]
+fn sign_bit<V: FloatVector>() -> V::Bits {
+    V::constant(-0.0).to_bits()
+}
+
+#[inline(always)]
+fn abs<V: FloatVector>(x: V) -> V {
+    V::from_bits(x.to_bits() & !sign_bit::<V>())
+}
+
+#[inline(always)]
+fn is_negative<V: FloatVector>(x: V) -> V::Mask {
+    V::nonzero(x.to_bits() & sign_bit::<V>())
+}
+
+/// The magnitude of `x` with the sign of `sign`.
+#[inline(always)]
+fn copysign<V: FloatVector>(x: V, sign: V) -> V {
+    V::from_bits(abs(x).to_bits() | (sign.to_bits() & sign_bit::<V>()))
+}
+
+/// `x` rounded to the nearest integer, as a float and as an integer.
+/// Only for `|x| < 2^(mantissa_bits - 1)`.
+#[inline(always)]
+fn round<V: FloatVector>(x: V) -> (V, V::Bits) {
+    let magic = V::constant(1.5 * V::C.two_pow_mantissa);
+    let t = x + magic;
+    (t - magic, t.to_bits() - magic.to_bits())
+}
+
+/// The inverse of the integer half of `round`.
+#[inline(always)]
+fn int_to_float<V: FloatVector>(n: V::Bits) -> V {
+    let magic = V::constant(1.5 * V::C.two_pow_mantissa);
+    V::from_bits(magic.to_bits() + n) - magic
+}
+
+#[inline(always)]
+fn is_integer<V: FloatVector>(x: V) -> V::Mask {
+    let big = V::constant(V::C.two_pow_mantissa);
+    let x = abs(x);
+    x.ge(big) | ((x + big) - big).eq(x)
+}
+
+/// `2^n`, for `n` in the normal range of exponents.
+#[inline(always)]
+fn pow2i<V: FloatVector>(n: V::Bits) -> V {
+    V::from_bits((n + V::int(V::C.exponent_bias)) << V::int(V::C.mantissa_bits))
+}
+
+// Arithmetic on unevaluated sums `hi + lo` (with `|lo|` below half an ULP of `hi`), which
+// carry about twice the precision of a float.
+
+/// `a + b` exactly, if `|a| >= |b|`.
+#[inline(always)]
+fn fast_two_sum<V: FloatVector>(a: V, b: V) -> (V, V) {
+    let s = a + b;
+    (s, b - (s - a))
+}
+
+/// `a + b` exactly.
+#[inline(always)]
+fn two_sum<V: FloatVector>(a: V, b: V) -> (V, V) {
+    let s = a + b;
+    let bb = s - a;
+    (s, (a - (s - bb)) + (b - bb))
+}
+
+#[inline(always)]
+fn split<V: FloatVector>(a: V) -> (V, V) {
+    let c = V::constant(V::C.split) * a;
+    let hi = c - (c - a);
+    (hi, a - hi)
+}
+
+/// `a * b` exactly, barring overflow and underflow.
+#[inline(always)]
+fn two_prod<V: FloatVector>(a: V, b: V) -> (V, V) {
+    let p = a * b;
+    let (a_hi, a_lo) = split(a);
+    let (b_hi, b_lo) = split(b);
+    (p, ((a_hi * b_hi - p) + a_hi * b_lo + a_lo * b_hi) + a_lo * b_lo)
+}
+
+#[inline(always)]
+fn select<V: FloatVector>(mask: V::Mask, a: V, b: V) -> V {
+    V::select(mask, a, b)
+}
+
+//--------------------------------------------------------------------------------
+
+/// The reciprocal square root of `x` scaled into the normal range, and the factor
+/// by which it must be multiplied to give that of `x`.
+#[inline(always)]
+fn rsqrt_scaled<V: FloatVector>(x: V) -> (V, V) {
+    let c = &V::C;
+    let one = V::constant(1.0);
+    let tiny = x.lt(V::constant(c.min_positive));
+    let huge = x.gt(V::constant(c.huge));
+    let scale = select(tiny, V::constant(c.scale), select(huge, V::constant(1.0 / c.scale), one));
+    let unscale = select(tiny, V::constant(c.scale_sqrt), select(huge, V::constant(1.0 / c.scale_sqrt), one));
+    let x = x * scale;
+
+    let half_x = V::constant(0.5) * x;
+    let bits = x.to_bits() >> V::int(1);
+    let mut y = V::from_bits(V::int(c.rsqrt_magic) - bits);
+    for _ in 0..c.newton_steps {
+        y = y + y * (V::constant(0.5) - half_x * (y * y));
+    }
+    (y, unscale)
+}
+
+#[inline(always)]
+fn rsqrt<V: FloatVector>(x: V) -> V {
+    let (y, unscale) = rsqrt_scaled(x);
+    let y = y * unscale;
+
+    let zero = V::constant(0.0);
+    let inf = V::constant(::std::f64::INFINITY);
+    let y = select(x.eq(inf), zero, y);
+    let y = select(x.eq(zero), V::constant(1.0) / x, y);
+    select(x.ge(zero), y, V::constant(::std::f64::NAN))
+}
+
+//--------------------------------------------------------------------------------
+
+/// `e^(r + r_lo) - 1` for `|r| <= ln(2) / 2`, as `hi + lo`.
+#[inline(always)]
+fn expm1_reduced_dd<V: FloatVector>(r: V, r_lo: V) -> (V, V) {
+    let one = V::constant(1.0);
+    fast_two_sum(r, r * r * poly(r, V::C.expm1) + r_lo * (one + r))
+}
+
+/// `(k, r, r_lo)` such that `hi + lo = k ln(2) + (r + r_lo)`, with `|r| <= ln(2) / 2`.
+#[inline(always)]
+fn exp_reduce<V: FloatVector>(hi: V, lo: V) -> (V::Bits, V, V) {
+    let c = &V::C;
+    let (n, k) = round(hi * V::constant(c.log2_e[0]));
+    let (r, r_lo) = two_sum(hi - n * V::constant(c.ln2[0]), lo - n * V::constant(c.ln2[1]));
+    (k, r, r_lo)
+}
+
+/// `e^(hi + lo)`, where `lo` is much smaller than `hi`.
+#[inline(always)]
+fn exp_dd<V: FloatVector>(hi: V, lo: V) -> V {
+    let limit = V::constant(V::C.exp_limit);
+    let lo = select(abs(hi).gt(limit), V::constant(0.0), lo);
+    let hi = select(hi.gt(limit), limit, hi);
+    let hi = select(hi.lt(-limit), -limit, hi);
+
+    let (k, r, r_lo) = exp_reduce(hi, lo);
+    let (q, q_lo) = expm1_reduced_dd(r, r_lo);
+    let p = V::constant(1.0) + (q + q_lo);
+    // scaling in two steps, so that both factors are normal, and a subnormal result is
+    // only rounded once
+    let k_half = k >> V::int(1);
+    p * pow2i(k_half) * pow2i(k - k_half)
+}
+
+#[inline(always)]
+fn exp<V: FloatVector>(x: V) -> V {
+    exp_dd(x, V::constant(0.0))
+}
+
+/// `e^x - 1`, for `0 <= x <= 2 * tanh_limit`, as `hi + lo`.
+#[inline(always)]
+fn expm1_positive_dd<V: FloatVector>(x: V) -> (V, V) {
+    let (k, r, r_lo) = exp_reduce(x, V::constant(0.0));
+    let (q, q_lo) = expm1_reduced_dd(r, r_lo);
+    let scale = pow2i::<V>(k);
+    let (e, e_lo) = two_sum(scale - V::constant(1.0), scale * q);
+    (e, e_lo + scale * q_lo)
+}
+
+#[inline(always)]
+fn tanh<V: FloatVector>(x: V) -> V {
+    let two = V::constant(2.0);
+    let a = abs(x);
+    // tanh(a) = (e^2a - 1) / (e^2a + 1), with the division carried to extra precision
+    let (e, e_lo) = expm1_positive_dd(two * a);
+    let (d, d_lo) = two_sum(e, two);
+    let d_lo = d_lo + e_lo;
+    let t = e / d;
+    let (td, td_lo) = two_prod(t, d);
+    let t = t + (((e - td) - td_lo) + (e_lo - t * d_lo)) / d;
+
+    let t = select(a.gt(V::constant(V::C.tanh_limit)), V::constant(1.0), t);
+    copysign(t, x)
+}
+
+//--------------------------------------------------------------------------------
+
+/// `(e, m)` such that `x = m 2^e` with `sqrt(1/2) <= m < sqrt(2)`, for positive finite `x`.
+#[inline(always)]
+fn frexp<V: FloatVector>(x: V) -> (V, V) {
+    let c = &V::C;
+    let zero = V::constant(0.0);
+    let tiny = x.lt(V::constant(c.min_positive));
+    let x = select(tiny, x * V::constant(c.scale), x);
+
+    let bits = x.to_bits();
+    let mantissa_mask = (V::int(1) << V::int(c.mantissa_bits)) - V::int(1);
+    let m = V::from_bits((bits & mantissa_mask) | V::constant(1.0).to_bits());
+    let e = int_to_float::<V>((bits >> V::int(c.mantissa_bits)) - V::int(c.exponent_bias));
+    let e = e - select(tiny, V::constant(c.scale_exponent), zero);
+
+    let big = m.gt(V::constant(::std::f64::consts::SQRT_2));
+    (select(big, e + V::constant(1.0), e), select(big, m * V::constant(0.5), m))
+}
+
+/// `ln(m)` for `sqrt(1/2) <= m < sqrt(2)`, as `hi + lo`.
+#[inline(always)]
+fn ln_reduced_dd<V: FloatVector>(m: V) -> (V, V) {
+    let c = &V::C;
+    // ln(1 + f) = 2 atanh(s) = 2 s + 2 s^3 / 3 + 2 s^5 / 5 + ...,  with s = f / (2 + f)
+    let f = m - V::constant(1.0);
+    let (t, t_lo) = fast_two_sum(V::constant(2.0), f);
+    let s = f / t;
+    let (st, st_lo) = two_prod(s, t);
+    let s_lo = (((f - st) - st_lo) - s * t_lo) / t;
+
+    // (the cubic term is still large enough to need the extra precision)
+    let (z, z_lo) = two_prod(s, s);
+    let (s3, s3_lo) = two_prod(s, z);
+    let s3_lo = s3_lo + s * z_lo;
+    let (cubic, cubic_lo) = two_prod(s3, V::constant(c.two_thirds[0]));
+    let cubic_lo = cubic_lo + (s3 * V::constant(c.two_thirds[1]) + s3_lo * V::constant(c.two_thirds[0]));
+    let rest = s3 * z * poly(z, c.ln);
+
+    let two = V::constant(2.0);
+    let (hi, lo) = fast_two_sum(two * s, cubic);
+    fast_two_sum(hi, lo + (two * s_lo + cubic_lo + (two * z * s_lo + rest)))
+}
+
+/// `ln(x)` for positive finite `x`, as `hi + lo`.
+#[inline(always)]
+fn ln_dd<V: FloatVector>(x: V) -> (V, V) {
+    let c = &V::C;
+    let (e, m) = frexp(x);
+    let (hi, lo) = ln_reduced_dd(m);
+    let (hi, err) = two_sum(e * V::constant(c.ln2[0]), hi);
+    fast_two_sum(hi, err + (lo + e * V::constant(c.ln2[1])))
+}
+
+/// The special cases of `ln` and `log2`.
+#[inline(always)]
+fn log_special<V: FloatVector>(x: V, y: V) -> V {
+    let zero = V::constant(0.0);
+    let inf = V::constant(::std::f64::INFINITY);
+    let y = select(x.eq(inf), inf, y);
+    let y = select(x.eq(zero), -inf, y);
+    select(x.ge(zero), y, V::constant(::std::f64::NAN))
+}
+
+#[inline(always)]
+fn ln<V: FloatVector>(x: V) -> V {
+    let (hi, lo) = ln_dd(x);
+    log_special(x, hi + lo)
+}
+
+#[inline(always)]
+fn log2<V: FloatVector>(x: V) -> V {
+    let c = &V::C;
+    let (e, m) = frexp(x);
+    let (hi, lo) = ln_reduced_dd(m);
+    let (y, y_lo) = two_prod(hi, V::constant(c.log2_e[0]));
+    let y_lo = y_lo + (lo * V::constant(c.log2_e[0]) + hi * V::constant(c.log2_e[1]));
+    let (y, err) = two_sum(e, y);
+    log_special(x, y + (err + y_lo))
+}
+
+#[inline(always)]
+fn pow<V: FloatVector>(x: V, y: V) -> V {
+    let c = &V::C;
+    let zero = V::constant(0.0);
+    let one = V::constant(1.0);
+    let inf = V::constant(::std::f64::INFINITY);
+    let nan = V::constant(::std::f64::NAN);
+
+    // |x|^y = e^(y ln|x|), with the product carried to extra precision
+    // (clamping y doesn't change the result, but keeps `two_prod` from overflowing)
+    let ax = abs(x);
+    let limit = V::constant(c.pow_limit);
+    let yc = select(y.gt(limit), limit, select(y.lt(-limit), -limit, y));
+    let (ln_hi, ln_lo) = ln_dd(ax);
+    let (p, p_lo) = two_prod(yc, ln_hi);
+    let result = exp_dd(p, p_lo + yc * ln_lo);
+
+    // zero or infinite |x| or y
+    let extreme = ax.eq(zero) | ax.eq(inf) | abs(y).eq(inf);
+    let big = ax.gt(one);
+    let positive = y.gt(zero);
+    let result = select(extreme, select((big & positive) | !(big | positive), inf, zero), result);
+
+    // negative x
+    let y_integer = is_integer(y);
+    let y_odd = y_integer & !is_integer(y * V::constant(0.5));
+    let result = select(is_negative(x) & y_odd, -result, result);
+    let result = select(x.lt(zero) & ax.ne(inf) & !y_integer, nan, result);
+
+    let result = select(x.ne(x) | y.ne(y), nan, result);
+    select(y.eq(zero) | x.eq(one) | (ax.eq(one) & abs(y).eq(inf)), one, result)
+}
+
+//--------------------------------------------------------------------------------
+
+/// `(k, r, r_lo)` such that `x = k pi/2 + (r + r_lo)`, with `|r| <= pi/4`.
+#[inline(always)]
+fn trig_reduce<V: FloatVector>(x: V) -> (V::Bits, V, V) {
+    let c = &V::C;
+    let (n, k) = round(x * V::constant(c.frac_2_pi));
+    let (first, rest) = c.pi_2.split_first().expect("no digits of pi");
+    let (r, r_lo) = rest.iter().fold((x - n * V::constant(*first), V::constant(0.0)), |(r, r_lo), &part| {
+        let (r, err) = two_sum(r, -(n * V::constant(part)));
+        (r, r_lo + err)
+    });
+    let (r, r_lo) = fast_two_sum(r, r_lo);
+    (k, r, r_lo)
+}
+
+/// `sin(r + r_lo)`
+#[inline(always)]
+fn sin_reduced<V: FloatVector>(r: V, r_lo: V) -> V {
+    let z = r * r;
+    r + (r * z * poly(z, V::C.sin) + r_lo * (V::constant(1.0) - V::constant(0.5) * z))
+}
+
+/// `cos(r + r_lo)`
+#[inline(always)]
+fn cos_reduced<V: FloatVector>(r: V, r_lo: V) -> V {
+    let one = V::constant(1.0);
+    let z = r * r;
+    let half_z = V::constant(0.5) * z;
+    let w = one - half_z;
+    w + (((one - w) - half_z) + (z * z * poly(z, V::C.cos) - r * r_lo))
+}
+
+// (huge arguments would need many more digits of pi/2 to reduce)
+#[inline(always)]
+fn trig<V, F, G>(x: V, fallback: F, func: G) -> V
+where
+    V: FloatVector,
+    F: Fn(V::Scalar) -> V::Scalar,
+    G: FnOnce(V::Bits, V, V) -> V,
+{
+    if abs(x).gt(V::constant(V::C.trig_limit)).any() {
+        return (0..V::WIDTH).fold(x, |out, i| out.replace(i, fallback(x.extract(i))));
+    }
+    let (k, r, r_lo) = trig_reduce(x);
+    func(k, sin_reduced(r, r_lo), cos_reduced(r, r_lo))
+}
+
+/// `sin(k pi/2 + r)`
+#[inline(always)]
+fn sin_quadrant<V: FloatVector>(k: V::Bits, sin: V, cos: V) -> V {
+    let y = select(V::nonzero(k & V::int(1)), cos, sin);
+    select(V::nonzero(k & V::int(2)), -y, y)
+}
+
+#[inline(always)]
+fn sin<V: FloatVector, F: Fn(V::Scalar) -> V::Scalar>(x: V, fallback: F) -> V {
+    trig(x, fallback, sin_quadrant)
+}
+
+#[inline(always)]
+fn cos<V: FloatVector, F: Fn(V::Scalar) -> V::Scalar>(x: V, fallback: F) -> V {
+    trig(x, fallback, |k, sin, cos| sin_quadrant(k + V::int(1), sin, cos))
+}
+
+#[inline(always)]
+fn tan<V: FloatVector, F: Fn(V::Scalar) -> V::Scalar>(x: V, fallback: F) -> V {
+    trig(x, fallback, |k, sin, cos| select(V::nonzero(k & V::int(1)), -cos / sin, sin / cos))
+}
+
+/// `atan(t + t_lo)` for `t >= 0`, as `hi + lo`.
+#[inline(always)]
+fn atan_positive_dd<V: FloatVector>(t: V, t_lo: V) -> (V, V) {
+    let c = &V::C;
+    let zero = V::constant(0.0);
+    let one = V::constant(1.0);
+    // atan(t) = pi/2 + atan(-1/t) = pi/4 + atan((t - 1) / (t + 1))
+    let big = t.gt(V::constant(c.tan_3pi_8));
+    let mid = t.gt(V::constant(c.tan_pi_8));
+    let (t_minus, t_minus_lo) = two_sum(t, -one);
+    let (t_plus, t_plus_lo) = two_sum(t, one);
+    let num = select(big, -one, select(mid, t_minus, t));
+    let num_lo = select(big, zero, select(mid, t_minus_lo + t_lo, t_lo));
+    let den = select(big, t, select(mid, t_plus, one));
+    let den_lo = select(big, t_lo, select(mid, t_plus_lo + t_lo, zero));
+    let base = select(big, V::constant(c.frac_pi_2[0]), select(mid, V::constant(c.frac_pi_4[0]), zero));
+    let base_lo = select(big, V::constant(c.frac_pi_2[1]), select(mid, V::constant(c.frac_pi_4[1]), zero));
+
+    // u + u_lo = (num + num_lo) / (den + den_lo)
+    let u = num / den;
+    let (ud, ud_lo) = two_prod(u, den);
+    let u_lo = (((num - ud) - ud_lo) + (num_lo - u * den_lo)) / den;
+    // (e.g. for infinite t)
+    let u_lo = select(u_lo.eq(u_lo), u_lo, zero);
+
+    let z = u * u;
+    let a = u + u * z * poly(z, c.atan);
+    // (the derivative of atan takes care of u_lo)
+    let (hi, lo) = fast_two_sum(base, a);
+    fast_two_sum(hi, lo + (base_lo + u_lo / (one + z)))
+}
+
+#[inline(always)]
+fn atan2<V: FloatVector>(y: V, x: V) -> V {
+    let c = &V::C;
+    let zero = V::constant(0.0);
+    let inf = V::constant(::std::f64::INFINITY);
+    let (ax, ay) = (abs(x), abs(y));
+    let left = is_negative(x);
+
+    let t = ay / ax;
+    let (tx, tx_lo) = two_prod(t, ax);
+    let t_lo = ((ay - tx) - tx_lo) / ax;
+    // (the product must not underflow or overflow for that to be exact)
+    let exact = ay.gt(V::constant(c.min_positive * c.scale)) & t_lo.eq(t_lo);
+    let t_lo = select(exact, t_lo, zero);
+    let (a, a_lo) = atan_positive_dd(t, t_lo);
+
+    let (pi_a, pi_a_lo) = two_sum(V::constant(c.pi[0]), -a);
+    let a = select(left, pi_a + (pi_a_lo + (V::constant(c.pi[1]) - a_lo)), a + a_lo);
+    // (where the ratio is 0/0 or inf/inf)
+    let a = select(ax.eq(zero) & ay.eq(zero), select(left, V::constant(c.pi[0]), zero), a);
+    let a = select(
+        ax.eq(inf) & ay.eq(inf),
+        select(left, V::constant(3.0 * c.frac_pi_4[0]), V::constant(c.frac_pi_4[0])),
+        a,
+    );
+    copysign(a, y)
+}
+
+//--------------------------------------------------------------------------------
+
+macro_rules! impl_primitive_packed_float {
+    ($($Scalar:ident: [$($Vector:ident),*];)*) => {$($(
+        impl PackedFloat for $Vector {
+            #[inline(always)]
+            fn sqrt(self) -> Self { <$Vector>::sqrt(self) }
+
+            #[inline(always)]
+            fn rsqrt(self) -> Self { rsqrt(self) }
+
+            #[inline(always)]
+            fn exp(self) -> Self { exp(self) }
+
+            #[inline(always)]
+            fn ln(self) -> Self { ln(self) }
+
+            #[inline(always)]
+            fn log2(self) -> Self { log2(self) }
+
+            #[inline(always)]
+            fn sin(self) -> Self { sin(self, $Scalar::sin) }
+
+            #[inline(always)]
+            fn cos(self) -> Self { cos(self, $Scalar::cos) }
+
+            #[inline(always)]
+            fn tan(self) -> Self { tan(self, $Scalar::tan) }
+
+            #[inline(always)]
+            fn atan2(self, x: Self) -> Self { atan2(self, x) }
+
+            #[inline(always)]
+            fn pow(self, y: Self) -> Self { pow(self, y) }
+
+            #[inline(always)]
+            fn tanh(self) -> Self { tanh(self) }
+        }
+    )*)*};
+}
+
+impl_primitive_packed_float!{
+    f32: [f32x2, f32x4, f32x8, f32x16];
+    f64: [f64x2, f64x4, f64x8];
+}
+
+macro_rules! impl_unary_for_cons_nil {
+    ($($method:ident),*) => {$(
+        #[inline(always)]
+        fn $method(self) -> Self { vlist![self.0.$method()] }
+    )*};
+}
+
+impl<V> PackedFloat for Cons<V, Nil>
+where
+    V: PackedFloat,
+{
+    impl_unary_for_cons_nil!{sqrt, rsqrt, exp, ln, log2, sin, cos, tan, tanh}
+
+    #[inline(always)]
+    fn atan2(self, x: Self) -> Self { vlist![self.0.atan2(x.0)] }
+
+    #[inline(always)]
+    fn pow(self, y: Self) -> Self { vlist![self.0.pow(y.0)] }
+}
+
+macro_rules! impl_unary_for_cons {
+    ($($method:ident),*) => {$(
+        #[inline(always)]
+        fn $method(self) -> Self { vlist![self.0.$method(), ...self.1.$method()] }
+    )*};
+}
+
+impl<V, Rest> PackedFloat for Cons<V, Rest>
+where
+    V: PackedFloat,
+    Rest: PackedFloat<Width = V::Width>,
+{
+    impl_unary_for_cons!{sqrt, rsqrt, exp, ln, log2, sin, cos, tan, tanh}
+
+    #[inline(always)]
+    fn atan2(self, x: Self) -> Self { vlist![self.0.atan2(x.0), ...self.1.atan2(x.1)] }
+
+    #[inline(always)]
+    fn pow(self, y: Self) -> Self { vlist![self.0.pow(y.0), ...self.1.pow(y.1)] }
+}
+
+macro_rules! impl_unary_for_custom {
+    ($($method:ident),*) => {$(
+        #[inline(always)]
+        fn $method(self) -> Self { T::vector_from_base(T::vector_into_base(self).$method()) }
+    )*};
+}
+
+/// Implements `PackedFloat` field by field for a `CustomPacked` type.
+///
+/// This is opt-in, because it is only correct when every field is an independent
+/// number; e.g. `Complex` doesn't implement it, as the square root of a complex
+/// number is not the square roots of its parts.
+///
+/// ```ignore
+/// #[derive(CustomPacked)]
+/// struct Point<V> { x: V, y: V }
+///
+/// impl<V: Packed> FieldwisePackedFloat for Point<V> {}
+/// ```
+pub trait FieldwisePackedFloat: CustomPacked {}
+
+impl<T> PackedFloat for T
+where
+    T: FieldwisePackedFloat,
+    T::BaseVector: PackedFloat,
+{
+    impl_unary_for_custom!{sqrt, rsqrt, exp, ln, log2, sin, cos, tan, tanh}
+
+    #[inline(always)]
+    fn atan2(self, x: Self) -> Self {
+        T::vector_from_base(T::vector_into_base(self).atan2(T::vector_into_base(x)))
+    }
+
+    #[inline(always)]
+    fn pow(self, y: Self) -> Self {
+        T::vector_from_base(T::vector_into_base(self).pow(T::vector_into_base(y)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::faster::{f32s, f64s};
+
+    trait Ulps: Copy + ::std::fmt::Debug {
+        /// The distance between two floats, in representable values.  (two NaNs are equal)
+        fn ulps(self, other: Self) -> u64;
+    }
+
+    macro_rules! impl_ulps {
+        ($($Float:ident: $Int:ident),*) => {$(
+            impl Ulps for $Float {
+                fn ulps(self, other: $Float) -> u64 {
+                    if self.is_nan() || other.is_nan() {
+                        return if self.is_nan() && other.is_nan() { 0 } else { !0 };
+                    }
+                    let ordered = |x: $Float| {
+                        let i = x.to_bits() as $Int;
+                        (if i < 0 { $Int::min_value() - i } else { i }) as i128
+                    };
+                    (ordered(self) - ordered(other)).abs() as u64
+                }
+            }
+        )*};
+    }
+
+    impl_ulps!{f32: i32, f64: i64}
+
+    // xorshift64*
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+            self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+        }
+
+        fn uniform(&mut self, lo: f64, hi: f64) -> f64 {
+            lo + (hi - lo) * ((self.next() >> 11) as f64 / (1u64 << 53) as f64)
+        }
+
+        // (every magnitude is equally likely, including subnormals, infinities and NaNs)
+        fn any_f64(&mut self) -> f64 { f64::from_bits(self.next()) }
+        fn any_f32(&mut self) -> f32 { f32::from_bits((self.next() >> 32) as u32) }
+    }
+
+    fn uniform(lo: f64, hi: f64) -> Vec<f64> {
+        let mut rng = Rng(0x1234_5678_9ABC_DEF0);
+        (0..4096).map(|_| rng.uniform(lo, hi)).collect()
+    }
+
+    fn any_f64() -> Vec<f64> {
+        let mut rng = Rng(0x0FED_CBA9_8765_4321);
+        (0..4096).map(|_| rng.any_f64()).chain(SPECIAL_F64.iter().cloned()).collect()
+    }
+
+    fn any_f32() -> Vec<f32> {
+        let mut rng = Rng(0x0FED_CBA9_8765_4321);
+        (0..4096).map(|_| rng.any_f32()).chain(SPECIAL_F64.iter().map(|&x| x as f32)).collect()
+    }
+
+    fn to_f32(xs: Vec<f64>) -> Vec<f32> { xs.into_iter().map(|x| x as f32).collect() }
+
+    const SPECIAL_F64: &[f64] = &[
+        0.0, -0.0, 1.0, -1.0, 0.5, -0.5, 2.0, -2.0, 3.0, -3.0, 1e-310, -1e-310,
+        ::std::f64::INFINITY, ::std::f64::NEG_INFINITY, ::std::f64::NAN,
+    ];
+
+    /// The worst error of `vector_fn` relative to `scalar_fn`, over every pair of inputs
+    /// `(xs[i], ys[i])`, along with those inputs.
+    fn worst<V, F, G>(xs: &[V::Scalar], ys: &[V::Scalar], vector_fn: F, scalar_fn: G) -> (u64, V::Scalar, V::Scalar)
+    where
+        V: Packed,
+        V::Scalar: Ulps,
+        F: Fn(V, V) -> V,
+        G: Fn(V::Scalar, V::Scalar) -> V::Scalar,
+    {
+        assert_eq!(xs.len(), ys.len());
+        let mut worst = (0, xs[0], ys[0]);
+        for start in (0..xs.len()).step_by(V::WIDTH) {
+            let lanes = |data: &[V::Scalar]| {
+                (0..V::WIDTH).fold(V::default(), |v, i| v.replace(i, data[(start + i) % data.len()]))
+            };
+            let out = vector_fn(lanes(xs), lanes(ys));
+            for i in 0..V::WIDTH {
+                let (x, y) = (xs[(start + i) % xs.len()], ys[(start + i) % ys.len()]);
+                let ulps = out.extract(i).ulps(scalar_fn(x, y));
+                if ulps > worst.0 {
+                    worst = (ulps, x, y);
+                }
+            }
+        }
+        worst
+    }
+
+    fn worst_unary<V, F, G>(xs: &[V::Scalar], vector_fn: F, scalar_fn: G) -> (u64, V::Scalar)
+    where
+        V: Packed,
+        V::Scalar: Ulps,
+        F: Fn(V) -> V,
+        G: Fn(V::Scalar) -> V::Scalar,
+    {
+        let (ulps, x, _) = worst(xs, xs, |v: V, _| vector_fn(v), |x, _| scalar_fn(x));
+        (ulps, x)
+    }
+
+    macro_rules! assert_ulps {
+        ($max:expr, $worst:expr) => {{
+            let worst = $worst;
+            assert!(worst.0 <= $max, "{} ulps at {:?}", worst.0, worst);
+        }};
+    }
+
+    fn abs_f64(xs: Vec<f64>) -> Vec<f64> { xs.into_iter().map(f64::abs).collect() }
+    fn abs_f32(xs: Vec<f32>) -> Vec<f32> { xs.into_iter().map(f32::abs).collect() }
+    fn rev<T>(mut xs: Vec<T>) -> Vec<T> { xs.reverse(); xs }
+
+    // (every pair of special values)
+    fn special_pairs() -> (Vec<f64>, Vec<f64>) {
+        let xs = SPECIAL_F64.iter().flat_map(|&x| SPECIAL_F64.iter().map(move |_| x)).collect();
+        let ys = SPECIAL_F64.iter().flat_map(|_| SPECIAL_F64.iter().cloned()).collect();
+        (xs, ys)
+    }
+
+    #[test]
+    fn roots() {
+        assert_ulps!(0, worst_unary(&any_f64(), <f64s as PackedFloat>::sqrt, f64::sqrt));
+        assert_ulps!(0, worst_unary(&any_f32(), <f32s as PackedFloat>::sqrt, f32::sqrt));
+        assert_ulps!(2, worst_unary(&abs_f64(any_f64()), <f64s as PackedFloat>::rsqrt, |x: f64| 1.0 / x.sqrt()));
+        assert_ulps!(2, worst_unary(&abs_f32(any_f32()), <f32s as PackedFloat>::rsqrt, |x: f32| 1.0 / x.sqrt()));
+        assert_eq!(PackedFloat::sqrt(f64s::splat(4.0)), f64s::splat(2.0));
+        assert_eq!(PackedFloat::rsqrt(f32x2::splat(0.25)), f32x2::splat(2.0));
+    }
+
+    #[test]
+    fn exp_and_logs() {
+        assert_ulps!(1, worst_unary(&uniform(-760.0, 760.0), <f64s as PackedFloat>::exp, f64::exp));
+        assert_ulps!(1, worst_unary(&any_f64(), <f64s as PackedFloat>::exp, f64::exp));
+        assert_ulps!(1, worst_unary(&to_f32(uniform(-110.0, 110.0)), <f32s as PackedFloat>::exp, f32::exp));
+        assert_ulps!(1, worst_unary(&any_f32(), <f32s as PackedFloat>::exp, f32::exp));
+
+        assert_ulps!(1, worst_unary(&uniform(0.5, 2.0), <f64s as PackedFloat>::ln, f64::ln));
+        assert_ulps!(1, worst_unary(&any_f64(), <f64s as PackedFloat>::ln, f64::ln));
+        assert_ulps!(1, worst_unary(&to_f32(uniform(0.5, 2.0)), <f32s as PackedFloat>::ln, f32::ln));
+        assert_ulps!(1, worst_unary(&any_f32(), <f32s as PackedFloat>::ln, f32::ln));
+
+        assert_ulps!(1, worst_unary(&uniform(0.5, 2.0), <f64s as PackedFloat>::log2, f64::log2));
+        assert_ulps!(1, worst_unary(&any_f64(), <f64s as PackedFloat>::log2, f64::log2));
+        assert_ulps!(1, worst_unary(&any_f32(), <f32s as PackedFloat>::log2, f32::log2));
+        let powers: Vec<f64> = (-1074..1024).map(|k| 2f64.powi(k)).collect();
+        assert_ulps!(0, worst_unary(&powers, <f64s as PackedFloat>::log2, f64::log2));
+    }
+
+    // (a vector function, the scalar function it approximates, and the ULP bound)
+    type Case<V, S> = (fn(V) -> V, fn(S) -> S, u64);
+
+    #[test]
+    fn trig() {
+        let cases: &[Case<f64s, f64>] = &[
+            (PackedFloat::sin, f64::sin, 1),
+            (PackedFloat::cos, f64::cos, 1),
+            (PackedFloat::tan, f64::tan, 2),
+        ];
+        for &(vector_fn, scalar_fn, max) in cases {
+            assert_ulps!(max, worst_unary(&uniform(-10.0, 10.0), vector_fn, scalar_fn));
+            assert_ulps!(max, worst_unary(&uniform(-1e5, 1e5), vector_fn, scalar_fn));
+            // (outside of the range, this is std itself)
+            assert_ulps!(max, worst_unary(&any_f64(), vector_fn, scalar_fn));
+        }
+
+        let cases: &[Case<f32s, f32>] = &[
+            (PackedFloat::sin, f32::sin, 1),
+            (PackedFloat::cos, f32::cos, 1),
+            (PackedFloat::tan, f32::tan, 2),
+        ];
+        for &(vector_fn, scalar_fn, max) in cases {
+            assert_ulps!(max, worst_unary(&to_f32(uniform(-10.0, 10.0)), vector_fn, scalar_fn));
+            assert_ulps!(max, worst_unary(&to_f32(uniform(-8192.0, 8192.0)), vector_fn, scalar_fn));
+            assert_ulps!(max, worst_unary(&any_f32(), vector_fn, scalar_fn));
+        }
+    }
+
+    #[test]
+    fn tanh() {
+        assert_ulps!(2, worst_unary(&uniform(-1.0, 1.0), <f64s as PackedFloat>::tanh, f64::tanh));
+        assert_ulps!(2, worst_unary(&uniform(-25.0, 25.0), <f64s as PackedFloat>::tanh, f64::tanh));
+        assert_ulps!(2, worst_unary(&any_f64(), <f64s as PackedFloat>::tanh, f64::tanh));
+        assert_ulps!(2, worst_unary(&to_f32(uniform(-12.0, 12.0)), <f32s as PackedFloat>::tanh, f32::tanh));
+        assert_ulps!(2, worst_unary(&any_f32(), <f32s as PackedFloat>::tanh, f32::tanh));
+    }
+
+    #[test]
+    fn atan2() {
+        let (ys, xs) = (uniform(-10.0, 10.0), rev(uniform(-10.0, 10.0)));
+        assert_ulps!(2, worst(&ys, &xs, <f64s as PackedFloat>::atan2, f64::atan2));
+        assert_ulps!(2, worst(&any_f64(), &rev(any_f64()), <f64s as PackedFloat>::atan2, f64::atan2));
+        assert_ulps!(2, worst(&to_f32(ys), &to_f32(xs), <f32s as PackedFloat>::atan2, f32::atan2));
+        assert_ulps!(2, worst(&any_f32(), &rev(any_f32()), <f32s as PackedFloat>::atan2, f32::atan2));
+
+        let (ys, xs) = special_pairs();
+        assert_ulps!(1, worst(&ys, &xs, <f64s as PackedFloat>::atan2, f64::atan2));
+        assert_ulps!(1, worst(&to_f32(ys), &to_f32(xs), <f32s as PackedFloat>::atan2, f32::atan2));
+    }
+
+    #[test]
+    fn pow() {
+        let (xs, ys) = (uniform(0.0, 4.0), rev(uniform(-10.0, 10.0)));
+        assert_ulps!(2, worst(&xs, &ys, <f64s as PackedFloat>::pow, f64::powf));
+        assert_ulps!(2, worst(&to_f32(xs), &to_f32(ys), <f32s as PackedFloat>::pow, f32::powf));
+        // (where the error in the log matters most)
+        let (xs, ys) = (uniform(0.7, 1.42), uniform(-2000.0, 2000.0));
+        assert_ulps!(2, worst(&xs, &ys, <f64s as PackedFloat>::pow, f64::powf));
+        let ys = to_f32(uniform(-250.0, 250.0));
+        assert_ulps!(2, worst(&to_f32(xs), &ys, <f32s as PackedFloat>::pow, f32::powf));
+        // (the worst case found so far, where `y * ln(x)` is large)
+        let (x, y) = (0.7063566298856231, 1998.0939873653542);
+        assert_ulps!(2, worst::<f64s, _, _>(&[x], &[y], PackedFloat::pow, f64::powf));
+
+        assert_ulps!(2, worst(&any_f64(), &rev(any_f64()), <f64s as PackedFloat>::pow, f64::powf));
+        assert_ulps!(2, worst(&any_f32(), &rev(any_f32()), <f32s as PackedFloat>::pow, f32::powf));
+
+        let (xs, ys) = special_pairs();
+        assert_ulps!(2, worst(&xs, &ys, <f64s as PackedFloat>::pow, f64::powf));
+        assert_ulps!(2, worst(&to_f32(xs), &to_f32(ys), <f32s as PackedFloat>::pow, f32::powf));
+    }
+
+    #[test]
+    fn widths() {
+        let xs = uniform(-3.0, 3.0);
+        assert_ulps!(1, worst_unary(&xs, <f64x2 as PackedFloat>::exp, f64::exp));
+        assert_ulps!(1, worst_unary(&xs, <f64x8 as PackedFloat>::sin, f64::sin));
+        assert_ulps!(1, worst_unary(&to_f32(xs.clone()), <f32x2 as PackedFloat>::cos, f32::cos));
+        assert_ulps!(1, worst_unary(&to_f32(xs), <f32x16 as PackedFloat>::sin, f32::sin));
+    }
+
+    #[derive(Debug, Copy, Clone, PartialEq, CustomPacked)]
+    #[custom_packed(root = "")]
+    struct Point<V> { x: V, y: V }
+
+    impl<V: Packed> FieldwisePackedFloat for Point<V> {}
+
+    #[test]
+    fn lifted() {
+        // (the fields must have equal widths, but not equal precisions)
+        let v = vlist![f64x2::splat(4.0), f32x2::splat(0.0)];
+        let vlist_pat![a, b] = v.sqrt();
+        assert_eq!(a, f64x2::splat(2.0));
+        assert_eq!(b, f32x2::splat(0.0));
+        let vlist_pat![a, b] = v.pow(vlist![f64x2::splat(0.5), f32x2::splat(2.0)]);
+        assert_ulps!(2, (Packed::extract(&a, 0).ulps(4f64.powf(0.5)), a));
+        assert_eq!(b, f32x2::splat(0.0));
+
+        // (each field is as accurate as the primitive function)
+        let p = Point { x: f64s::splat(1.0), y: f64s::splat(-2.0) };
+        let q = Point { x: f64s::splat(0.0), y: f64s::splat(-1.0) };
+        let ulps = |v: f64s, expected: f64| (0..f64s::WIDTH).map(|i| Packed::extract(&v, i).ulps(expected)).max().unwrap();
+        let sin = p.sin();
+        assert_ulps!(1, (ulps(sin.x, 1f64.sin()), sin));
+        assert_ulps!(1, (ulps(sin.y, (-2f64).sin()), sin));
+        let atan2 = p.atan2(q);
+        assert_ulps!(2, (ulps(atan2.x, 1f64.atan2(0.0)), atan2));
+        assert_ulps!(2, (ulps(atan2.y, (-2f64).atan2(-1.0)), atan2));
+    }
+}
@@ -1,25 +1,22 @@
-use ::vlist::{Cons, Nil};
-use ::faster::vecs::f64x2;
-
-pub trait TypeLevelUsize {
-    const VALUE: usize;
-}
-
-// TODO could use typenum (or it could be overkill),
-//      or maybe some sort of "exponential Peano" encoding
-//      (type P8 = Double<Double<Double<P1>>>;)
-pub enum P1 {}
-pub enum P2 {}
-pub enum P4 {}
-pub enum P8 {}
-pub enum P16 {}
-pub enum P32 {}
-impl TypeLevelUsize for P1 { const VALUE: usize = 1; }
-impl TypeLevelUsize for P2 { const VALUE: usize = 2; }
-impl TypeLevelUsize for P4 { const VALUE: usize = 4; }
-impl TypeLevelUsize for P8 { const VALUE: usize = 8; }
-impl TypeLevelUsize for P16 { const VALUE: usize = 16; }
-impl TypeLevelUsize for P32 { const VALUE: usize = 32; }
+use ::std::cmp;
+use ::vlist::{Cons, Nil, IntoTuple};
+use ::typelevel::{TypeLevelUsize, Min, Minimum};
+use ::mask::{self, PackedMask};
+use ::index;
+use ::typelevel::{U2, U4, U8, U16, U32, U64};
+use ::faster::vecs::{f32x4, f32x8, f32x16, f64x2, f64x4, f64x8};
+use ::faster::vecs::{i8x16, i8x32, i8x64, i16x8, i16x16, i16x32};
+use ::faster::vecs::{i32x4, i32x8, i32x16, i64x2, i64x4, i64x8};
+use ::faster::vecs::{u8x16, u8x32, u8x64, u16x8, u16x16, u16x32};
+use ::faster::vecs::{u32x4, u32x8, u32x16, u64x2, u64x4, u64x8};
+// (these are too narrow to be the native vector for anything, but they're useful
+//  when zipping with a type that has fewer lanes)
+use ::std::simd::{f32x2, i8x2, i8x4, i8x8, i16x2, i16x4, i32x2};
+use ::std::simd::{u8x2, u8x4, u8x8, u16x2, u16x4, u32x2};
+use ::std::simd::{m8x2, m8x4, m8x8, m8x16, m8x32, m8x64};
+use ::std::simd::{m16x2, m16x4, m16x8, m16x16, m16x32};
+use ::std::simd::{m32x2, m32x4, m32x8, m32x16};
+use ::std::simd::{m64x2, m64x4, m64x8};
 
 /// Holds generic associated types for Packed.
 pub trait PackedGats<'a> {
@@ -41,6 +38,12 @@ pub trait Packed: for<'a> PackedGats<'a> {
     type Width: TypeLevelUsize;
     const WIDTH: usize = Self::Width::VALUE;
 
+    /// The result of a lane-wise comparison.
+    ///
+    /// For VLists and custom types, this is a VList with one mask per field.
+    /// (see `PackedMask::lanes_all` to get a single mask out of it)
+    type Mask: PackedMask<Width = Self::Width>;
+
     fn load_unaligned<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self;
 
     unsafe fn load_unaligned_unchecked<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self;
@@ -49,8 +52,91 @@ pub trait Packed: for<'a> PackedGats<'a> {
 
     unsafe fn store_unaligned_unchecked<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut);
 
+    /// Like `load_unaligned`, but requires every slice to be aligned to the size of its vector.
+    ///
+    /// Panics if any of them are not.
+    fn load_aligned<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self;
+
+    /// Like `load_unaligned_unchecked`, but the slices must also be aligned
+    /// to the size of their vectors.
+    unsafe fn load_aligned_unchecked<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self;
+
+    /// Like `store_unaligned`, but requires every slice to be aligned to the size of its vector.
+    ///
+    /// Panics if any of them are not.
+    fn store_aligned<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut);
+
+    /// Like `store_unaligned_unchecked`, but the slices must also be aligned
+    /// to the size of their vectors.
+    unsafe fn store_aligned_unchecked<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut);
+
+    /// Loads the first `min(len, WIDTH)` lanes from `data`, and fills the rest with `fill`.
+    ///
+    /// (for VLists and custom types, each field's slice is considered separately)
+    fn load_partial<'a>(data: <Self as PackedGats<'a>>::ScalarSlice, fill: Self::Scalar) -> Self;
+
+    /// Stores the first `min(len, WIDTH)` lanes into `data`; the lanes that don't fit are dropped.
+    fn store_partial<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut);
+
+    /// Loads lane `i` from index `indices[i]` of the slice.
+    ///
+    /// For VLists and custom types, the same indices are used for every field.
+    /// Panics if any index is out of bounds.
+    fn gather<'a, I>(data: <Self as PackedGats<'a>>::ScalarSlice, indices: I) -> Self
+    where I: index::IndexVector<Width = Self::Width>;
+
+    unsafe fn gather_unchecked<'a, I>(data: <Self as PackedGats<'a>>::ScalarSlice, indices: I) -> Self
+    where I: index::IndexVector<Width = Self::Width>;
+
+    /// Stores lane `i` at index `indices[i]` of the slice.
+    ///
+    /// For VLists and custom types, the same indices are used for every field.
+    /// If an index appears more than once, the highest lane wins.
+    /// Panics if any index is out of bounds, in which case nothing is written.
+    #[inline(always)]
+    fn scatter<'a, I>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut, indices: I)
+    where
+        Self: Sized,
+        I: index::IndexVector<Width = Self::Width>,
+    {
+        let len = Self::_scalar_slice_mut_len(&data);
+        for i in 0..Self::WIDTH {
+            assert!(indices.index(i) < len, "scatter: index out of bounds");
+        }
+        unsafe { self.scatter_unchecked(data, indices) }
+    }
+
+    unsafe fn scatter_unchecked<'a, I>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut, indices: I)
+    where I: index::IndexVector<Width = Self::Width>;
+
     fn coalesce(self) -> Self::Scalar;
 
+    /// Lane-wise `==`.
+    fn eq(self, other: Self) -> Self::Mask;
+
+    /// Lane-wise `!=`.
+    fn ne(self, other: Self) -> Self::Mask;
+
+    /// Lane-wise `<`.
+    fn lt(self, other: Self) -> Self::Mask;
+
+    /// Lane-wise `<=`.
+    fn le(self, other: Self) -> Self::Mask;
+
+    /// Lane-wise `>`.
+    fn gt(self, other: Self) -> Self::Mask;
+
+    /// Lane-wise `>=`.
+    fn ge(self, other: Self) -> Self::Mask;
+
+    /// Take each lane of each field from `a` where `mask` is set, and from `b` elsewhere.
+    fn select(mask: Self::Mask, a: Self, b: Self) -> Self;
+
+    /// Like `select`, but one mask decides between whole elements (every field of a lane).
+    ///
+    /// (e.g. a `Complex<f64s>` can be selected with an `m64x4`)
+    fn select_lanes(mask: <Self::Mask as PackedMask>::Lanes, a: Self, b: Self) -> Self;
+
     #[inline(always)]
     fn extract(&self, idx: usize) -> Self::Scalar {
         Self::_proxy_extract(self.as_packed_ref(), idx)
@@ -68,6 +154,10 @@ pub trait Packed: for<'a> PackedGats<'a> {
 
     unsafe fn _proxy_extract_unchecked<'a>(refs: <Self as PackedGats<'a>>::Ref, idx: usize) -> Self::Scalar;
 
+    // (the length of the shortest slice in `data`, so that `scatter` can check its indices
+    //  before writing anything)
+    fn _scalar_slice_mut_len<'a>(data: &<Self as PackedGats<'a>>::ScalarSliceMut) -> usize;
+
     fn replace(self, idx: usize, data: Self::Scalar) -> Self;
 
     unsafe fn replace_unchecked(self, idx: usize, data: Self::Scalar) -> Self;
@@ -91,98 +181,294 @@ pub trait Packed: for<'a> PackedGats<'a> {
 /// (the ones in std)
 pub trait PrimitivePacked: Packed {
     // (this is here rather than on Packed because it would be a disaster to have e.g.
-    //  a `Complex::product` that does independent products of the reals and imaginaries)
+    //  a `Complex::product` that does independent products of the reals and imaginaries.
+    //  Sums don't have that problem, and are available field-wise through `PackedSum`)
+
+    /// The sum of all lanes.  (this wraps on overflow for integers)
+    fn sum(&self) -> Self::Scalar;
+
+    /// The product of all lanes.  (this wraps on overflow for integers)
     fn product(&self) -> Self::Scalar;
-}
 
-impl<'a> PackedGats<'a> for f64x2 {
-    type Ref = &'a Self;
-    type RefMut = &'a mut Self;
-    type ScalarSlice = &'a [f64];
-    type ScalarSliceMut = &'a mut [f64];
+    /// The smallest lane.
+    ///
+    /// If any lanes are NaN, the result may or may not be NaN.
+    fn min(&self) -> Self::Scalar;
 
-    #[inline(always)]
-    fn as_packed_ref(&'a self) -> Self::Ref { self }
-    #[inline(always)]
-    fn as_packed_mut(&'a mut self) -> Self::RefMut { self }
+    /// The largest lane.
+    ///
+    /// If any lanes are NaN, the result may or may not be NaN.
+    fn max(&self) -> Self::Scalar;
 }
 
-impl Packed for f64x2 {
-    type Scalar = f64;
-    type Width = P2;
+/// Bitwise reductions, for the primitive integer vectors.
+pub trait PrimitiveIntPacked: PrimitivePacked {
+    /// The bitwise `&` of all lanes.
+    fn and(&self) -> Self::Scalar;
 
-    #[inline(always)]
-    fn load_unaligned<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self {
-        <f64x2>::load_unaligned(data)
-    }
+    /// The bitwise `|` of all lanes.
+    fn or(&self) -> Self::Scalar;
 
-    #[inline(always)]
-    unsafe fn load_unaligned_unchecked<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self {
-        debug_assert!(data.len() >= Self::WIDTH);
-        <f64x2>::load_unaligned_unchecked(data)
-    }
+    /// The bitwise `^` of all lanes.
+    fn xor(&self) -> Self::Scalar;
+}
 
-    #[inline(always)]
-    fn store_unaligned<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
-        <f64x2>::store_unaligned(self, data);
-    }
+/// Whether a slice is suitably aligned for aligned loads and stores of `V`.
+#[inline(always)]
+fn is_aligned<V, T>(data: &[T]) -> bool {
+    data.as_ptr() as usize % ::std::mem::size_of::<V>() == 0
+}
 
-    #[inline(always)]
-    unsafe fn store_unaligned_unchecked<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
-        debug_assert!(data.len() >= Self::WIDTH);
-        <f64x2>::store_unaligned_unchecked(self, data);
-    }
+#[inline(always)]
+fn assert_aligned<V, T>(data: &[T], what: &str) {
+    assert!(
+        is_aligned::<V, T>(data),
+        "{}: slice at {:p} is not aligned to {} bytes",
+        what, data.as_ptr(), ::std::mem::size_of::<V>(),
+    );
+}
 
-    #[inline(always)]
-    fn coalesce(self) -> Self::Scalar {
-        for i in 1..Self::WIDTH {
-            debug_assert_eq!(self.extract(i - 1), self.extract(i));
+macro_rules! impl_primitive_packed {
+    ($($Vector:ident: [$Scalar:ty; $Width:ty] => $Mask:ident,)*) => {$(
+        impl<'a> PackedGats<'a> for $Vector {
+            type Ref = &'a Self;
+            type RefMut = &'a mut Self;
+            type ScalarSlice = &'a [$Scalar];
+            type ScalarSliceMut = &'a mut [$Scalar];
+
+            #[inline(always)]
+            fn as_packed_ref(&'a self) -> Self::Ref { self }
+            #[inline(always)]
+            fn as_packed_mut(&'a mut self) -> Self::RefMut { self }
         }
-        self.extract(0)
-    }
-
-    #[inline(always)]
-    fn _proxy_extract<'a>(refs: <Self as PackedGats<'a>>::Ref, idx: usize) -> Self::Scalar {
-        <f64x2>::extract(*refs, idx)
-    }
-
-    #[inline(always)]
-    unsafe fn _proxy_extract_unchecked<'a>(refs: <Self as PackedGats<'a>>::Ref, idx: usize) -> Self::Scalar {
-        debug_assert!(idx < Self::WIDTH);
-        <f64x2>::extract_unchecked(*refs, idx)
-    }
-
-    #[inline(always)]
-    fn replace(self, idx: usize, data: Self::Scalar) -> Self {
-        <f64x2>::replace(self, idx, data)
-    }
 
-    #[inline(always)]
-    unsafe fn replace_unchecked(self, idx: usize, data: Self::Scalar) -> Self {
-        debug_assert!(idx < Self::WIDTH);
-        <f64x2>::replace_unchecked(self, idx, data)
-    }
-
-    #[inline(always)]
-    fn splat(data: Self::Scalar) -> Self {
-        <f64x2>::splat(data)
-    }
-
-    #[inline(always)]
-    fn default() -> Self {
-        <f64x2>::splat(Self::Scalar::default())
-    }
+        impl Packed for $Vector {
+            type Scalar = $Scalar;
+            type Width = $Width;
+            type Mask = $Mask;
+
+            #[inline(always)]
+            fn load_unaligned<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self {
+                <$Vector>::load_unaligned(data)
+            }
+
+            #[inline(always)]
+            unsafe fn load_unaligned_unchecked<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self {
+                debug_assert!(data.len() >= Self::WIDTH);
+                <$Vector>::load_unaligned_unchecked(data)
+            }
+
+            #[inline(always)]
+            fn store_unaligned<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
+                <$Vector>::store_unaligned(self, data);
+            }
+
+            #[inline(always)]
+            unsafe fn store_unaligned_unchecked<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
+                debug_assert!(data.len() >= Self::WIDTH);
+                <$Vector>::store_unaligned_unchecked(self, data);
+            }
+
+            #[inline(always)]
+            fn load_aligned<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self {
+                assert_aligned::<Self, _>(data, "load_aligned");
+                <$Vector>::load_aligned(data)
+            }
+
+            #[inline(always)]
+            unsafe fn load_aligned_unchecked<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self {
+                debug_assert!(data.len() >= Self::WIDTH);
+                debug_assert!(is_aligned::<Self, _>(data));
+                <$Vector>::load_aligned_unchecked(data)
+            }
+
+            #[inline(always)]
+            fn store_aligned<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
+                assert_aligned::<Self, _>(data, "store_aligned");
+                <$Vector>::store_aligned(self, data);
+            }
+
+            #[inline(always)]
+            unsafe fn store_aligned_unchecked<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
+                debug_assert!(data.len() >= Self::WIDTH);
+                debug_assert!(is_aligned::<Self, _>(data));
+                <$Vector>::store_aligned_unchecked(self, data);
+            }
+
+            #[inline(always)]
+            fn load_partial<'a>(data: <Self as PackedGats<'a>>::ScalarSlice, fill: $Scalar) -> Self {
+                if data.len() >= Self::WIDTH {
+                    return <$Vector>::load_unaligned(data);
+                }
+                let mut out = <$Vector>::splat(fill);
+                for (i, &x) in data.iter().enumerate() {
+                    out = unsafe { out.replace_unchecked(i, x) };
+                }
+                out
+            }
+
+            #[inline(always)]
+            fn store_partial<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
+                if data.len() >= Self::WIDTH {
+                    return <$Vector>::store_unaligned(self, data);
+                }
+                for (i, x) in data.iter_mut().enumerate() {
+                    *x = unsafe { self.extract_unchecked(i) };
+                }
+            }
+
+            // FIXME: These could use hardware gathers/scatters where available.
+            #[inline(always)]
+            fn gather<'a, I>(data: <Self as PackedGats<'a>>::ScalarSlice, indices: I) -> Self
+            where I: index::IndexVector<Width = Self::Width>,
+            {
+                let mut out = <$Vector>::splat(Default::default());
+                for i in 0..Self::WIDTH {
+                    out = unsafe { out.replace_unchecked(i, data[indices.index_unchecked(i)]) };
+                }
+                out
+            }
+
+            #[inline(always)]
+            unsafe fn gather_unchecked<'a, I>(data: <Self as PackedGats<'a>>::ScalarSlice, indices: I) -> Self
+            where I: index::IndexVector<Width = Self::Width>,
+            {
+                let mut out = <$Vector>::splat(Default::default());
+                for i in 0..Self::WIDTH {
+                    let idx = indices.index_unchecked(i);
+                    debug_assert!(idx < data.len());
+                    out = out.replace_unchecked(i, *data.get_unchecked(idx));
+                }
+                out
+            }
+
+            #[inline(always)]
+            unsafe fn scatter_unchecked<'a, I>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut, indices: I)
+            where I: index::IndexVector<Width = Self::Width>,
+            {
+                for i in 0..Self::WIDTH {
+                    let idx = indices.index_unchecked(i);
+                    debug_assert!(idx < data.len());
+                    *data.get_unchecked_mut(idx) = self.extract_unchecked(i);
+                }
+            }
+
+            #[inline(always)]
+            fn coalesce(self) -> Self::Scalar {
+                for i in 1..Self::WIDTH {
+                    debug_assert_eq!(self.extract(i - 1), self.extract(i));
+                }
+                self.extract(0)
+            }
+
+            #[inline(always)]
+            fn eq(self, other: Self) -> $Mask { <$Vector>::eq(self, other) }
+
+            #[inline(always)]
+            fn ne(self, other: Self) -> $Mask { <$Vector>::ne(self, other) }
+
+            #[inline(always)]
+            fn lt(self, other: Self) -> $Mask { <$Vector>::lt(self, other) }
+
+            #[inline(always)]
+            fn le(self, other: Self) -> $Mask { <$Vector>::le(self, other) }
+
+            #[inline(always)]
+            fn gt(self, other: Self) -> $Mask { <$Vector>::gt(self, other) }
+
+            #[inline(always)]
+            fn ge(self, other: Self) -> $Mask { <$Vector>::ge(self, other) }
+
+            #[inline(always)]
+            fn select(mask: $Mask, a: Self, b: Self) -> Self { mask.select(a, b) }
+
+            #[inline(always)]
+            fn select_lanes(mask: $Mask, a: Self, b: Self) -> Self { mask.select(a, b) }
+
+            #[inline(always)]
+            fn _proxy_extract<'a>(refs: <Self as PackedGats<'a>>::Ref, idx: usize) -> Self::Scalar {
+                <$Vector>::extract(*refs, idx)
+            }
+
+            #[inline(always)]
+            unsafe fn _proxy_extract_unchecked<'a>(refs: <Self as PackedGats<'a>>::Ref, idx: usize) -> Self::Scalar {
+                debug_assert!(idx < Self::WIDTH);
+                <$Vector>::extract_unchecked(*refs, idx)
+            }
+
+            #[inline(always)]
+            fn _scalar_slice_mut_len<'a>(data: &<Self as PackedGats<'a>>::ScalarSliceMut) -> usize {
+                data.len()
+            }
+
+            #[inline(always)]
+            fn replace(self, idx: usize, data: Self::Scalar) -> Self {
+                <$Vector>::replace(self, idx, data)
+            }
+
+            #[inline(always)]
+            unsafe fn replace_unchecked(self, idx: usize, data: Self::Scalar) -> Self {
+                debug_assert!(idx < Self::WIDTH);
+                <$Vector>::replace_unchecked(self, idx, data)
+            }
+
+            #[inline(always)]
+            fn splat(data: Self::Scalar) -> Self {
+                <$Vector>::splat(data)
+            }
+
+            #[inline(always)]
+            fn default() -> Self {
+                <$Vector>::splat(Self::Scalar::default())
+            }
+        }
+    )*};
 }
 
-impl PrimitivePacked for f64x2 {
-    #[inline(always)]
-    fn product(&self) -> Self::Scalar {
-        let mut acc = 1 as Self::Scalar;
-        for i in 0..Self::WIDTH {
-            acc *= self.extract(i)
-        }
-        acc
-    }
+impl_primitive_packed!{
+    f32x2: [f32; U2] => m32x2,
+    f32x4: [f32; U4] => m32x4,
+    f32x8: [f32; U8] => m32x8,
+    f32x16: [f32; U16] => m32x16,
+    f64x2: [f64; U2] => m64x2,
+    f64x4: [f64; U4] => m64x4,
+    f64x8: [f64; U8] => m64x8,
+    i8x2: [i8; U2] => m8x2,
+    i8x4: [i8; U4] => m8x4,
+    i8x8: [i8; U8] => m8x8,
+    i8x16: [i8; U16] => m8x16,
+    i8x32: [i8; U32] => m8x32,
+    i8x64: [i8; U64] => m8x64,
+    i16x2: [i16; U2] => m16x2,
+    i16x4: [i16; U4] => m16x4,
+    i16x8: [i16; U8] => m16x8,
+    i16x16: [i16; U16] => m16x16,
+    i16x32: [i16; U32] => m16x32,
+    i32x2: [i32; U2] => m32x2,
+    i32x4: [i32; U4] => m32x4,
+    i32x8: [i32; U8] => m32x8,
+    i32x16: [i32; U16] => m32x16,
+    i64x2: [i64; U2] => m64x2,
+    i64x4: [i64; U4] => m64x4,
+    i64x8: [i64; U8] => m64x8,
+    u8x2: [u8; U2] => m8x2,
+    u8x4: [u8; U4] => m8x4,
+    u8x8: [u8; U8] => m8x8,
+    u8x16: [u8; U16] => m8x16,
+    u8x32: [u8; U32] => m8x32,
+    u8x64: [u8; U64] => m8x64,
+    u16x2: [u16; U2] => m16x2,
+    u16x4: [u16; U4] => m16x4,
+    u16x8: [u16; U8] => m16x8,
+    u16x16: [u16; U16] => m16x16,
+    u16x32: [u16; U32] => m16x32,
+    u32x2: [u32; U2] => m32x2,
+    u32x4: [u32; U4] => m32x4,
+    u32x8: [u32; U8] => m32x8,
+    u32x16: [u32; U16] => m32x16,
+    u64x2: [u64; U2] => m64x2,
+    u64x4: [u64; U4] => m64x4,
+    u64x8: [u64; U8] => m64x8,
 }
 
 impl<'a, V> PackedGats<'a> for Cons<V, Nil>
@@ -237,6 +523,7 @@ where
 {
     type Scalar = Cons<V::Scalar, Nil>;
     type Width = V::Width;
+    type Mask = Cons<V::Mask, Nil>;
 
     #[inline(always)]
     fn load_unaligned<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self {
@@ -264,12 +551,117 @@ where
         v.store_unaligned_unchecked(data);
     }
 
+    #[inline(always)]
+    fn load_aligned<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self {
+        let vlist_pat![data] = data;
+        vlist![V::load_aligned(data)]
+    }
+
+    #[inline(always)]
+    unsafe fn load_aligned_unchecked<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self {
+        let vlist_pat![data] = data;
+        vlist![V::load_aligned_unchecked(data)]
+    }
+
+    #[inline(always)]
+    fn store_aligned<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
+        let vlist_pat![v] = self;
+        let vlist_pat![data] = data;
+        v.store_aligned(data);
+    }
+
+    #[inline(always)]
+    unsafe fn store_aligned_unchecked<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
+        let vlist_pat![v] = self;
+        let vlist_pat![data] = data;
+        v.store_aligned_unchecked(data);
+    }
+
+    #[inline(always)]
+    fn load_partial<'a>(data: <Self as PackedGats<'a>>::ScalarSlice, fill: Self::Scalar) -> Self {
+        let vlist_pat![data] = data;
+        let vlist_pat![fill] = fill;
+        vlist![V::load_partial(data, fill)]
+    }
+
+    #[inline(always)]
+    fn store_partial<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
+        let vlist_pat![v] = self;
+        let vlist_pat![data] = data;
+        v.store_partial(data);
+    }
+
+    #[inline(always)]
+    fn gather<'a, I>(data: <Self as PackedGats<'a>>::ScalarSlice, indices: I) -> Self
+    where I: index::IndexVector<Width = Self::Width>,
+    {
+        let vlist_pat![data] = data;
+        vlist![V::gather(data, indices)]
+    }
+
+    #[inline(always)]
+    unsafe fn gather_unchecked<'a, I>(data: <Self as PackedGats<'a>>::ScalarSlice, indices: I) -> Self
+    where I: index::IndexVector<Width = Self::Width>,
+    {
+        let vlist_pat![data] = data;
+        vlist![V::gather_unchecked(data, indices)]
+    }
+
+    #[inline(always)]
+    unsafe fn scatter_unchecked<'a, I>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut, indices: I)
+    where I: index::IndexVector<Width = Self::Width>,
+    {
+        let vlist_pat![v] = self;
+        let vlist_pat![data] = data;
+        v.scatter_unchecked(data, indices);
+    }
+
     #[inline(always)]
     fn coalesce(self) -> Self::Scalar {
         let vlist_pat![v] = self;
         vlist![v.coalesce()]
     }
 
+    #[inline(always)]
+    fn eq(self, other: Self) -> Self::Mask {
+        vlist![self.0.eq(other.0)]
+    }
+
+    #[inline(always)]
+    fn ne(self, other: Self) -> Self::Mask {
+        vlist![self.0.ne(other.0)]
+    }
+
+    #[inline(always)]
+    fn lt(self, other: Self) -> Self::Mask {
+        vlist![self.0.lt(other.0)]
+    }
+
+    #[inline(always)]
+    fn le(self, other: Self) -> Self::Mask {
+        vlist![self.0.le(other.0)]
+    }
+
+    #[inline(always)]
+    fn gt(self, other: Self) -> Self::Mask {
+        vlist![self.0.gt(other.0)]
+    }
+
+    #[inline(always)]
+    fn ge(self, other: Self) -> Self::Mask {
+        vlist![self.0.ge(other.0)]
+    }
+
+    #[inline(always)]
+    fn select(mask: Self::Mask, a: Self, b: Self) -> Self {
+        vlist![V::select(mask.0, a.0, b.0)]
+    }
+
+    #[inline(always)]
+    fn select_lanes(mask: <Self::Mask as PackedMask>::Lanes, a: Self, b: Self) -> Self {
+        vlist![V::select_lanes(mask, a.0, b.0)]
+    }
+
     #[inline(always)]
     fn _proxy_extract<'a>(refs: <Self as PackedGats<'a>>::Ref, idx: usize) -> Self::Scalar {
         let vlist_pat![v] = refs;
@@ -282,6 +674,11 @@ where
         vlist![V::_proxy_extract_unchecked(v, idx)]
     }
 
+    #[inline(always)]
+    fn _scalar_slice_mut_len<'a>(data: &<Self as PackedGats<'a>>::ScalarSliceMut) -> usize {
+        V::_scalar_slice_mut_len(&data.0)
+    }
+
     #[inline(always)]
     fn replace(self, idx: usize, data: Self::Scalar) -> Self {
         let vlist_pat![v] = self;
@@ -315,6 +712,7 @@ where
 {
     type Scalar = Cons<V::Scalar, Rest::Scalar>;
     type Width = V::Width;
+    type Mask = Cons<V::Mask, Rest::Mask>;
 
     #[inline(always)]
     fn load_unaligned<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self {
@@ -350,12 +748,154 @@ where
         rest.store_unaligned_unchecked(data_rest);
     }
 
+    #[inline(always)]
+    fn load_aligned<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self {
+        let vlist_pat![v, ...rest] = data;
+        vlist![
+            V::load_aligned(v),
+            ...Rest::load_aligned(rest)
+        ]
+    }
+
+    #[inline(always)]
+    unsafe fn load_aligned_unchecked<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self {
+        let vlist_pat![v, ...rest] = data;
+        vlist![
+            V::load_aligned_unchecked(v),
+            ...Rest::load_aligned_unchecked(rest)
+        ]
+    }
+
+    #[inline(always)]
+    fn store_aligned<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
+        let vlist_pat![v, ...rest] = self;
+        let vlist_pat![data_v, ...data_rest] = data;
+        v.store_aligned(data_v);
+        rest.store_aligned(data_rest);
+    }
+
+    #[inline(always)]
+    unsafe fn store_aligned_unchecked<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
+        let vlist_pat![v, ...rest] = self;
+        let vlist_pat![data_v, ...data_rest] = data;
+        v.store_aligned_unchecked(data_v);
+        rest.store_aligned_unchecked(data_rest);
+    }
+
+    #[inline(always)]
+    fn load_partial<'a>(data: <Self as PackedGats<'a>>::ScalarSlice, fill: Self::Scalar) -> Self {
+        let vlist_pat![v, ...rest] = data;
+        let vlist_pat![fill_v, ...fill_rest] = fill;
+        vlist![
+            V::load_partial(v, fill_v),
+            ...Rest::load_partial(rest, fill_rest)
+        ]
+    }
+
+    #[inline(always)]
+    fn store_partial<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
+        let vlist_pat![v, ...rest] = self;
+        let vlist_pat![data_v, ...data_rest] = data;
+        v.store_partial(data_v);
+        rest.store_partial(data_rest);
+    }
+
+    #[inline(always)]
+    fn gather<'a, I>(data: <Self as PackedGats<'a>>::ScalarSlice, indices: I) -> Self
+    where I: index::IndexVector<Width = Self::Width>,
+    {
+        let vlist_pat![v, ...rest] = data;
+        vlist![
+            V::gather(v, indices),
+            ...Rest::gather(rest, indices)
+        ]
+    }
+
+    #[inline(always)]
+    unsafe fn gather_unchecked<'a, I>(data: <Self as PackedGats<'a>>::ScalarSlice, indices: I) -> Self
+    where I: index::IndexVector<Width = Self::Width>,
+    {
+        let vlist_pat![v, ...rest] = data;
+        vlist![
+            V::gather_unchecked(v, indices),
+            ...Rest::gather_unchecked(rest, indices)
+        ]
+    }
+
+    #[inline(always)]
+    unsafe fn scatter_unchecked<'a, I>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut, indices: I)
+    where I: index::IndexVector<Width = Self::Width>,
+    {
+        let vlist_pat![v, ...rest] = self;
+        let vlist_pat![data_v, ...data_rest] = data;
+        v.scatter_unchecked(data_v, indices);
+        rest.scatter_unchecked(data_rest, indices);
+    }
+
     #[inline(always)]
     fn coalesce(self) -> Self::Scalar {
         let vlist_pat![v, ...rest] = self;
         vlist![v.coalesce(), ...rest.coalesce()]
     }
 
+    #[inline(always)]
+    fn eq(self, other: Self) -> Self::Mask {
+        let vlist_pat![v, ...rest] = self;
+        let vlist_pat![other_v, ...other_rest] = other;
+        vlist![v.eq(other_v), ...rest.eq(other_rest)]
+    }
+
+    #[inline(always)]
+    fn ne(self, other: Self) -> Self::Mask {
+        let vlist_pat![v, ...rest] = self;
+        let vlist_pat![other_v, ...other_rest] = other;
+        vlist![v.ne(other_v), ...rest.ne(other_rest)]
+    }
+
+    #[inline(always)]
+    fn lt(self, other: Self) -> Self::Mask {
+        let vlist_pat![v, ...rest] = self;
+        let vlist_pat![other_v, ...other_rest] = other;
+        vlist![v.lt(other_v), ...rest.lt(other_rest)]
+    }
+
+    #[inline(always)]
+    fn le(self, other: Self) -> Self::Mask {
+        let vlist_pat![v, ...rest] = self;
+        let vlist_pat![other_v, ...other_rest] = other;
+        vlist![v.le(other_v), ...rest.le(other_rest)]
+    }
+
+    #[inline(always)]
+    fn gt(self, other: Self) -> Self::Mask {
+        let vlist_pat![v, ...rest] = self;
+        let vlist_pat![other_v, ...other_rest] = other;
+        vlist![v.gt(other_v), ...rest.gt(other_rest)]
+    }
+
+    #[inline(always)]
+    fn ge(self, other: Self) -> Self::Mask {
+        let vlist_pat![v, ...rest] = self;
+        let vlist_pat![other_v, ...other_rest] = other;
+        vlist![v.ge(other_v), ...rest.ge(other_rest)]
+    }
+
+    #[inline(always)]
+    fn select(mask: Self::Mask, a: Self, b: Self) -> Self {
+        let vlist_pat![mask_v, ...mask_rest] = mask;
+        let vlist_pat![a_v, ...a_rest] = a;
+        let vlist_pat![b_v, ...b_rest] = b;
+        vlist![V::select(mask_v, a_v, b_v), ...Rest::select(mask_rest, a_rest, b_rest)]
+    }
+
+    #[inline(always)]
+    fn select_lanes(mask: <Self::Mask as PackedMask>::Lanes, a: Self, b: Self) -> Self {
+        let rest_mask = mask::cast_lanes(mask);
+        let vlist_pat![a_v, ...a_rest] = a;
+        let vlist_pat![b_v, ...b_rest] = b;
+        vlist![V::select_lanes(mask, a_v, b_v), ...Rest::select_lanes(rest_mask, a_rest, b_rest)]
+    }
+
     #[inline(always)]
     fn _proxy_extract<'a>(refs: <Self as PackedGats<'a>>::Ref, idx: usize) -> Self::Scalar {
         let vlist_pat![v, ...rest] = refs;
@@ -374,6 +914,11 @@ where
         ]
     }
 
+    #[inline(always)]
+    fn _scalar_slice_mut_len<'a>(data: &<Self as PackedGats<'a>>::ScalarSliceMut) -> usize {
+        cmp::min(V::_scalar_slice_mut_len(&data.0), Rest::_scalar_slice_mut_len(&data.1))
+    }
+
     #[inline(always)]
     fn replace(self, idx: usize, data: Self::Scalar) -> Self {
         let vlist_pat![v, ...rest] = self;
@@ -410,16 +955,78 @@ pub trait Packable: Sized {
 }
 
 /// Complementary methods to Packable that are only implemented on true primitive scalar types.
-pub trait PrimitivePackable: Packable {
+pub trait PrimitivePackable: Packable + Copy {
     const SIZE: usize;
 }
 
-impl Packable for f64 {
-    type Vector = ::faster::f64s;
+macro_rules! impl_primitive_packable {
+    ($($Scalar:ident: $Vector:ident, $SIZE:expr;)*) => {$(
+        impl Packable for $Scalar {
+            type Vector = ::faster::$Vector;
+        }
+
+        impl PrimitivePackable for $Scalar {
+            const SIZE: usize = $SIZE;
+        }
+    )*};
+}
+
+impl_primitive_packable!{
+    f32: f32s, 32;
+    f64: f64s, 64;
+    i8: i8s, 8;
+    i16: i16s, 16;
+    i32: i32s, 32;
+    i64: i64s, 64;
+    u8: u8s, 8;
+    u16: u16s, 16;
+    u32: u32s, 32;
+    u64: u64s, 64;
+}
+
+/// Looks up the vector type with a specific number of lanes for a scalar.
+///
+/// This is how `Packable` zips scalars whose natural vector types have different widths.
+pub trait PackableAt<W: TypeLevelUsize>: Sized {
+    type Vector: Packed<Scalar = Self, Width = W> + Clone;
+}
+
+macro_rules! impl_primitive_packable_at {
+    ($($Scalar:ident: [$($Width:ident: $Vector:ident),*];)*) => {$($(
+        impl PackableAt<$Width> for $Scalar {
+            type Vector = $Vector;
+        }
+    )*)*};
+}
+
+impl_primitive_packable_at!{
+    f32: [U2: f32x2, U4: f32x4, U8: f32x8, U16: f32x16];
+    f64: [U2: f64x2, U4: f64x4, U8: f64x8];
+    i8: [U2: i8x2, U4: i8x4, U8: i8x8, U16: i8x16, U32: i8x32, U64: i8x64];
+    i16: [U2: i16x2, U4: i16x4, U8: i16x8, U16: i16x16, U32: i16x32];
+    i32: [U2: i32x2, U4: i32x4, U8: i32x8, U16: i32x16];
+    i64: [U2: i64x2, U4: i64x4, U8: i64x8];
+    u8: [U2: u8x2, U4: u8x4, U8: u8x8, U16: u8x16, U32: u8x32, U64: u8x64];
+    u16: [U2: u16x2, U4: u16x4, U8: u16x8, U16: u16x16, U32: u16x32];
+    u32: [U2: u32x2, U4: u32x4, U8: u32x8, U16: u32x16];
+    u64: [U2: u64x2, U4: u64x4, U8: u64x8];
 }
 
-impl PrimitivePackable for f64 {
-    const SIZE: usize = 64;
+impl<X, W> PackableAt<W> for Cons<X, Nil>
+where
+    X: PackableAt<W>,
+    W: TypeLevelUsize,
+{
+    type Vector = Cons<X::Vector, Nil>;
+}
+
+impl<A, B, Rest, W> PackableAt<W> for Cons<A, Cons<B, Rest>>
+where
+    A: PackableAt<W>,
+    Cons<B, Rest>: PackableAt<W>,
+    W: TypeLevelUsize,
+{
+    type Vector = Cons<A::Vector, <Cons<B, Rest> as PackableAt<W>>::Vector>;
 }
 
 impl<X> Packable for Cons<X, Nil>
@@ -429,35 +1036,36 @@ where
     type Vector = Cons<X::Vector, Nil>;
 }
 
-// TODO: Less restrictive Packable impls that would allow types where
-//       `<A as Packable>::Vector::Width != <B as Packable>::Vector::Width`.
-//       I think it should take the minimum of the two widths, and then construct
-//       vectors entirely of that width. (typenum can help there)
-//
-//       ...it's going to be a lot of type-level programming.
-//              - ML
+/// The width chosen by `Packable` for `Cons<A, Rest>`.
+type ConsWidth<A, Rest> = Minimum<
+    <<A as Packable>::Vector as Packed>::Width,
+    <<Rest as Packable>::Vector as Packed>::Width,
+>;
 
+// When the elements have different natural widths, the minimum is used for all of them.
+// (e.g. `VList![f32, f64]` becomes `VList![f32x4, f64x4]` on AVX)
+//
 // FIXME: This should be for Cons<V, Rest>, like the Packed impl
 impl<A, B, Rest> Packable for Cons<A, Cons<B, Rest>>
 where
     A: Packable,
-    B: Packable,
-    Rest: Packable,
-    B::Vector: Packed<Width=<A::Vector as Packed>::Width>,
-    Rest::Vector: Packed<Width=<A::Vector as Packed>::Width>,
+    Cons<B, Rest>: Packable,
+    <A::Vector as Packed>::Width: Min<<<Cons<B, Rest> as Packable>::Vector as Packed>::Width>,
+    // (this one is implied, but leaving it out sends rustc into a spiral)
+    ConsWidth<A, Cons<B, Rest>>: TypeLevelUsize,
+    Self: PackableAt<ConsWidth<A, Cons<B, Rest>>>,
 {
-    type Vector = Cons<A::Vector, Cons<B::Vector, Rest::Vector>>;
+    type Vector = <Self as PackableAt<ConsWidth<A, Cons<B, Rest>>>>::Vector;
 }
 
 //--------------------------------------------------------------------------------
 
-// TODO: impl CustomPacked for tuples
-
 /// Can be implemented to create a user-defined `Packed` type.
 ///
 /// `Packed` has a blanket impl for types which implement this trait.
 pub trait CustomPacked: Sized {
     // FIXME: Guh!! Users need to write 3 associated types and 6 methods? This is awful!
+    //        (`#[derive(CustomPacked)]` from zip-simd-derive will write them for you)
 
     /// The desired scalar type.
     type CustomScalar;
@@ -503,6 +1111,7 @@ where
 {
     type Scalar = T::CustomScalar;
     type Width = <T::BaseVector as Packed>::Width;
+    type Mask = <T::BaseVector as Packed>::Mask;
 
     #[inline(always)]
     fn load_unaligned<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self {
@@ -524,11 +1133,102 @@ where
         T::vector_into_base(self).store_unaligned_unchecked(data)
     }
 
+    #[inline(always)]
+    fn load_aligned<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self {
+        T::vector_from_base(T::BaseVector::load_aligned(data))
+    }
+
+    #[inline(always)]
+    unsafe fn load_aligned_unchecked<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self {
+        T::vector_from_base(T::BaseVector::load_aligned_unchecked(data))
+    }
+
+    #[inline(always)]
+    fn store_aligned<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
+        T::vector_into_base(self).store_aligned(data)
+    }
+
+    #[inline(always)]
+    unsafe fn store_aligned_unchecked<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
+        T::vector_into_base(self).store_aligned_unchecked(data)
+    }
+
+    #[inline(always)]
+    fn load_partial<'a>(data: <Self as PackedGats<'a>>::ScalarSlice, fill: Self::Scalar) -> Self {
+        T::vector_from_base(T::BaseVector::load_partial(data, T::scalar_into_base(fill)))
+    }
+
+    #[inline(always)]
+    fn store_partial<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
+        T::vector_into_base(self).store_partial(data)
+    }
+
+    #[inline(always)]
+    fn gather<'a, I>(data: <Self as PackedGats<'a>>::ScalarSlice, indices: I) -> Self
+    where I: index::IndexVector<Width = Self::Width>,
+    {
+        T::vector_from_base(T::BaseVector::gather(data, indices))
+    }
+
+    #[inline(always)]
+    unsafe fn gather_unchecked<'a, I>(data: <Self as PackedGats<'a>>::ScalarSlice, indices: I) -> Self
+    where I: index::IndexVector<Width = Self::Width>,
+    {
+        T::vector_from_base(T::BaseVector::gather_unchecked(data, indices))
+    }
+
+    #[inline(always)]
+    unsafe fn scatter_unchecked<'a, I>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut, indices: I)
+    where I: index::IndexVector<Width = Self::Width>,
+    {
+        T::vector_into_base(self).scatter_unchecked(data, indices)
+    }
+
     #[inline(always)]
     fn coalesce(self) -> Self::Scalar {
         T::scalar_from_base(T::vector_into_base(self).coalesce())
     }
 
+    #[inline(always)]
+    fn eq(self, other: Self) -> Self::Mask {
+        T::vector_into_base(self).eq(T::vector_into_base(other))
+    }
+
+    #[inline(always)]
+    fn ne(self, other: Self) -> Self::Mask {
+        T::vector_into_base(self).ne(T::vector_into_base(other))
+    }
+
+    #[inline(always)]
+    fn lt(self, other: Self) -> Self::Mask {
+        T::vector_into_base(self).lt(T::vector_into_base(other))
+    }
+
+    #[inline(always)]
+    fn le(self, other: Self) -> Self::Mask {
+        T::vector_into_base(self).le(T::vector_into_base(other))
+    }
+
+    #[inline(always)]
+    fn gt(self, other: Self) -> Self::Mask {
+        T::vector_into_base(self).gt(T::vector_into_base(other))
+    }
+
+    #[inline(always)]
+    fn ge(self, other: Self) -> Self::Mask {
+        T::vector_into_base(self).ge(T::vector_into_base(other))
+    }
+
+    #[inline(always)]
+    fn select(mask: Self::Mask, a: Self, b: Self) -> Self {
+        T::vector_from_base(T::BaseVector::select(mask, T::vector_into_base(a), T::vector_into_base(b)))
+    }
+
+    #[inline(always)]
+    fn select_lanes(mask: <Self::Mask as PackedMask>::Lanes, a: Self, b: Self) -> Self {
+        T::vector_from_base(T::BaseVector::select_lanes(mask, T::vector_into_base(a), T::vector_into_base(b)))
+    }
+
     #[inline(always)]
     fn _proxy_extract<'a>(refs: <Self as PackedGats<'a>>::Ref, idx: usize) -> Self::Scalar {
         T::scalar_from_base(T::BaseVector::_proxy_extract(refs, idx))
@@ -539,6 +1239,11 @@ where
         T::scalar_from_base(T::BaseVector::_proxy_extract_unchecked(refs, idx))
     }
 
+    #[inline(always)]
+    fn _scalar_slice_mut_len<'a>(data: &<Self as PackedGats<'a>>::ScalarSliceMut) -> usize {
+        T::BaseVector::_scalar_slice_mut_len(data)
+    }
+
     #[inline(always)]
     fn replace(self, idx: usize, data: Self::Scalar) -> Self {
         let base = T::vector_into_base(self);
@@ -566,37 +1271,351 @@ where
 
 //--------------------------------------------------------------------------------
 
-mod test {
-    use super::*;
+// Tuples are packed through the equivalent VList.
+//
+// `(f64s, f64s)` is a `Packed` type with scalar `(f64, f64)`,
+// and `(f32, f64)` is `Packable` with the same widths as `VList![f32, f64]`.
+macro_rules! impl_packed_for_tuples {
+    () => {};
+    ($A:ident $a:ident, $($B:ident $b:ident,)*) => {
+        impl<$A, $($B),*> CustomPacked for ($A, $($B,)*)
+        where
+            $A: Packed,
+            $($B: Packed<Width = $A::Width>,)*
+        {
+            type CustomScalar = ($A::Scalar, $($B::Scalar,)*);
+            type BaseScalar = VList![$A::Scalar, $($B::Scalar),*];
+            type BaseVector = VList![$A, $($B),*];
+
+            #[inline(always)]
+            fn vector_into_base(vector: Self) -> Self::BaseVector { vector.into() }
+
+            #[inline(always)]
+            fn vector_from_base(vector: Self::BaseVector) -> Self { vector.into_tuple() }
+
+            #[inline(always)]
+            fn scalar_into_base(scalar: Self::CustomScalar) -> Self::BaseScalar { scalar.into() }
+
+            #[inline(always)]
+            fn scalar_from_base(scalar: Self::BaseScalar) -> Self::CustomScalar { scalar.into_tuple() }
+
+            #[inline(always)]
+            fn vector_as_base<'a>(vector: &'a Self) -> <Self::BaseVector as PackedGats<'a>>::Ref {
+                let (ref $a, $(ref $b,)*) = *vector;
+                vlist![$a.as_packed_ref(), $($b.as_packed_ref()),*]
+            }
+
+            #[inline(always)]
+            fn vector_as_base_mut<'a>(vector: &'a mut Self) -> <Self::BaseVector as PackedGats<'a>>::RefMut {
+                let (ref mut $a, $(ref mut $b,)*) = *vector;
+                vlist![$a.as_packed_mut(), $($b.as_packed_mut()),*]
+            }
+        }
 
-    #[derive(Debug, Copy, Clone, PartialEq)]
-    struct Complex<V> { real: V, imag: V }
+        impl<$A, $($B),*> Packable for ($A, $($B,)*)
+        where
+            VList![$A, $($B),*]: Packable,
+            <VList![$A, $($B),*] as Packable>::Vector: IntoTuple,
+            <<VList![$A, $($B),*] as Packable>::Vector as IntoTuple>::Tuple: Packed<Scalar = Self> + Clone,
+        {
+            type Vector = <<VList![$A, $($B),*] as Packable>::Vector as IntoTuple>::Tuple;
+        }
 
-    // Did somebody say ***BOILERPLATE?***
-    impl<V: Packed> CustomPacked for Complex<V> {
-        type CustomScalar = Complex<V::Scalar>;
-        type BaseScalar = Cons<V::Scalar, Cons<V::Scalar, Nil>>;
-        type BaseVector = Cons<V, Cons<V, Nil>>;
+        impl_packed_for_tuples!{ $($B $b,)* }
+    };
+}
 
-        fn vector_into_base(Complex { real, imag }: Self) -> Self::BaseVector {
-            vlist![real, imag]
-        }
-        fn vector_from_base(vlist_pat![real, imag]: Self::BaseVector) -> Self {
-            Complex { real, imag }
-        }
-        fn scalar_into_base(Complex { real, imag }: Self::CustomScalar) -> Self::BaseScalar {
-            vlist![real, imag]
-        }
-        fn scalar_from_base(vlist_pat![real, imag]: Self::BaseScalar) -> Self::CustomScalar {
-            Complex { real, imag }
+impl_packed_for_tuples!{
+    A a, B b, C c, D d, E e, F f, G g, H h, I i, J j, K k, L l,
+}
+
+//--------------------------------------------------------------------------------
+
+// Expands to `$T`, once for each `$ignored` it is repeated alongside.
+macro_rules! repeat_type {
+    ($T:ty; $ignored:ident) => { $T };
+}
+
+// Arrays are homogeneous VLists, but with arrays of slices instead of VLists of slices.
+//
+// e.g. `[f64s; 3]` has scalar `[f64; 3]` and loads from `[&xs[..], &ys[..], &zs[..]]`.
+macro_rules! impl_packed_for_arrays {
+    ($($N:expr => [$($v:ident $d:ident),*];)*) => {$(
+        impl<'a, V> PackedGats<'a> for [V; $N]
+        where
+            V: PackedGats<'a>,
+        {
+            type Ref = [V::Ref; $N];
+            type RefMut = [V::RefMut; $N];
+            type ScalarSlice = [V::ScalarSlice; $N];
+            type ScalarSliceMut = [V::ScalarSliceMut; $N];
+
+            #[inline(always)]
+            fn as_packed_ref(&'a self) -> Self::Ref {
+                let [$(ref $v),*] = *self;
+                [$($v.as_packed_ref()),*]
+            }
+
+            #[inline(always)]
+            fn as_packed_mut(&'a mut self) -> Self::RefMut {
+                let [$(ref mut $v),*] = *self;
+                [$($v.as_packed_mut()),*]
+            }
         }
-        fn vector_as_base<'a>(Complex { real, imag }: &'a Self) -> <Self::BaseVector as PackedGats<'a>>::Ref {
-            vlist![real.as_packed_ref(), imag.as_packed_ref()]
+
+        impl<V> Packed for [V; $N]
+        where
+            V: Packed,
+        {
+            type Scalar = [V::Scalar; $N];
+            type Width = V::Width;
+            type Mask = VList![$(repeat_type!(V::Mask; $v)),*];
+
+            #[inline(always)]
+            fn load_unaligned<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self {
+                let [$($v),*] = data;
+                [$(V::load_unaligned($v)),*]
+            }
+
+            #[inline(always)]
+            unsafe fn load_unaligned_unchecked<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self {
+                let [$($v),*] = data;
+                [$(V::load_unaligned_unchecked($v)),*]
+            }
+
+            #[inline(always)]
+            fn store_unaligned<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
+                let [$($v),*] = self;
+                let [$($d),*] = data;
+                $($v.store_unaligned($d);)*
+            }
+
+            #[inline(always)]
+            unsafe fn store_unaligned_unchecked<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
+                let [$($v),*] = self;
+                let [$($d),*] = data;
+                $($v.store_unaligned_unchecked($d);)*
+            }
+
+            #[inline(always)]
+            fn load_aligned<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self {
+                let [$($v),*] = data;
+                [$(V::load_aligned($v)),*]
+            }
+
+            #[inline(always)]
+            unsafe fn load_aligned_unchecked<'a>(data: <Self as PackedGats<'a>>::ScalarSlice) -> Self {
+                let [$($v),*] = data;
+                [$(V::load_aligned_unchecked($v)),*]
+            }
+
+            #[inline(always)]
+            fn store_aligned<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
+                let [$($v),*] = self;
+                let [$($d),*] = data;
+                $($v.store_aligned($d);)*
+            }
+
+            #[inline(always)]
+            unsafe fn store_aligned_unchecked<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
+                let [$($v),*] = self;
+                let [$($d),*] = data;
+                $($v.store_aligned_unchecked($d);)*
+            }
+
+            #[inline(always)]
+            fn load_partial<'a>(data: <Self as PackedGats<'a>>::ScalarSlice, fill: Self::Scalar) -> Self {
+                let [$($v),*] = data;
+                let [$($d),*] = fill;
+                [$(V::load_partial($v, $d)),*]
+            }
+
+            #[inline(always)]
+            fn store_partial<'a>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut) {
+                let [$($v),*] = self;
+                let [$($d),*] = data;
+                $($v.store_partial($d);)*
+            }
+
+            #[inline(always)]
+            fn gather<'a, I>(data: <Self as PackedGats<'a>>::ScalarSlice, indices: I) -> Self
+            where I: index::IndexVector<Width = Self::Width>,
+            {
+                let [$($v),*] = data;
+                [$(V::gather($v, indices)),*]
+            }
+
+            #[inline(always)]
+            unsafe fn gather_unchecked<'a, I>(data: <Self as PackedGats<'a>>::ScalarSlice, indices: I) -> Self
+            where I: index::IndexVector<Width = Self::Width>,
+            {
+                let [$($v),*] = data;
+                [$(V::gather_unchecked($v, indices)),*]
+            }
+
+            #[inline(always)]
+            unsafe fn scatter_unchecked<'a, I>(self, data: <Self as PackedGats<'a>>::ScalarSliceMut, indices: I)
+            where I: index::IndexVector<Width = Self::Width>,
+            {
+                let [$($v),*] = self;
+                let [$($d),*] = data;
+                $($v.scatter_unchecked($d, indices);)*
+            }
+
+            #[inline(always)]
+            fn coalesce(self) -> Self::Scalar {
+                let [$($v),*] = self;
+                [$($v.coalesce()),*]
+            }
+
+            #[inline(always)]
+            fn eq(self, other: Self) -> Self::Mask {
+                let [$($v),*] = self;
+                let [$($d),*] = other;
+                vlist![$($v.eq($d)),*]
+            }
+
+            #[inline(always)]
+            fn ne(self, other: Self) -> Self::Mask {
+                let [$($v),*] = self;
+                let [$($d),*] = other;
+                vlist![$($v.ne($d)),*]
+            }
+
+            #[inline(always)]
+            fn lt(self, other: Self) -> Self::Mask {
+                let [$($v),*] = self;
+                let [$($d),*] = other;
+                vlist![$($v.lt($d)),*]
+            }
+
+            #[inline(always)]
+            fn le(self, other: Self) -> Self::Mask {
+                let [$($v),*] = self;
+                let [$($d),*] = other;
+                vlist![$($v.le($d)),*]
+            }
+
+            #[inline(always)]
+            fn gt(self, other: Self) -> Self::Mask {
+                let [$($v),*] = self;
+                let [$($d),*] = other;
+                vlist![$($v.gt($d)),*]
+            }
+
+            #[inline(always)]
+            fn ge(self, other: Self) -> Self::Mask {
+                let [$($v),*] = self;
+                let [$($d),*] = other;
+                vlist![$($v.ge($d)),*]
+            }
+
+            #[inline(always)]
+            fn select(mask: Self::Mask, a: Self, b: Self) -> Self {
+                // (pair up the masks with `a` first, so that two sets of bindings suffice)
+                let vlist_pat![$($v),*] = mask;
+                let [$($d),*] = a;
+                let [$($v),*] = [$(($v, $d)),*];
+                let [$($d),*] = b;
+                [$(V::select($v.0, $v.1, $d)),*]
+            }
+
+            #[inline(always)]
+            fn select_lanes(mask: <Self::Mask as PackedMask>::Lanes, a: Self, b: Self) -> Self {
+                let [$($v),*] = a;
+                let [$($d),*] = b;
+                [$(V::select_lanes(mask, $v, $d)),*]
+            }
+
+            #[inline(always)]
+            fn _proxy_extract<'a>(refs: <Self as PackedGats<'a>>::Ref, idx: usize) -> Self::Scalar {
+                let [$($v),*] = refs;
+                [$(V::_proxy_extract($v, idx)),*]
+            }
+
+            #[inline(always)]
+            unsafe fn _proxy_extract_unchecked<'a>(refs: <Self as PackedGats<'a>>::Ref, idx: usize) -> Self::Scalar {
+                let [$($v),*] = refs;
+                [$(V::_proxy_extract_unchecked($v, idx)),*]
+            }
+
+            #[inline(always)]
+            fn _scalar_slice_mut_len<'a>(data: &<Self as PackedGats<'a>>::ScalarSliceMut) -> usize {
+                data.iter().fold(usize::max_value(), |len, d| cmp::min(len, V::_scalar_slice_mut_len(d)))
+            }
+
+            #[inline(always)]
+            fn replace(self, idx: usize, data: Self::Scalar) -> Self {
+                let [$($v),*] = self;
+                let [$($d),*] = data;
+                [$($v.replace(idx, $d)),*]
+            }
+
+            #[inline(always)]
+            unsafe fn replace_unchecked(self, idx: usize, data: Self::Scalar) -> Self {
+                let [$($v),*] = self;
+                let [$($d),*] = data;
+                [$($v.replace_unchecked(idx, $d)),*]
+            }
+
+            #[inline(always)]
+            fn splat(data: Self::Scalar) -> Self {
+                let [$($v),*] = data;
+                [$(V::splat($v)),*]
+            }
+
+            #[inline(always)]
+            fn default() -> Self {
+                [$({ let $v = <V as Packed>::default(); $v }),*]
+            }
         }
-        fn vector_as_base_mut<'a>(Complex { real, imag }: &'a mut Self) -> <Self::BaseVector as PackedGats<'a>>::RefMut {
-            vlist![real.as_packed_mut(), imag.as_packed_mut()]
+
+        impl<T> Packable for [T; $N]
+        where
+            T: Packable,
+        {
+            type Vector = [T::Vector; $N];
         }
-    }
+    )*};
+}
+
+impl_packed_for_arrays!{
+    1 => [v0 d0];
+    2 => [v0 d0, v1 d1];
+    3 => [v0 d0, v1 d1, v2 d2];
+    4 => [v0 d0, v1 d1, v2 d2, v3 d3];
+    5 => [v0 d0, v1 d1, v2 d2, v3 d3, v4 d4];
+    6 => [v0 d0, v1 d1, v2 d2, v3 d3, v4 d4, v5 d5];
+    7 => [v0 d0, v1 d1, v2 d2, v3 d3, v4 d4, v5 d5, v6 d6];
+    8 => [v0 d0, v1 d1, v2 d2, v3 d3, v4 d4, v5 d5, v6 d6, v7 d7];
+    9 => [v0 d0, v1 d1, v2 d2, v3 d3, v4 d4, v5 d5, v6 d6, v7 d7, v8 d8];
+    10 => [v0 d0, v1 d1, v2 d2, v3 d3, v4 d4, v5 d5, v6 d6, v7 d7, v8 d8, v9 d9];
+    11 => [v0 d0, v1 d1, v2 d2, v3 d3, v4 d4, v5 d5, v6 d6, v7 d7, v8 d8, v9 d9, v10 d10];
+    12 => [v0 d0, v1 d1, v2 d2, v3 d3, v4 d4, v5 d5, v6 d6, v7 d7, v8 d8, v9 d9, v10 d10, v11 d11];
+    13 => [v0 d0, v1 d1, v2 d2, v3 d3, v4 d4, v5 d5, v6 d6, v7 d7, v8 d8, v9 d9, v10 d10, v11 d11, v12 d12];
+    14 => [v0 d0, v1 d1, v2 d2, v3 d3, v4 d4, v5 d5, v6 d6, v7 d7, v8 d8, v9 d9, v10 d10, v11 d11, v12 d12, v13 d13];
+    15 => [v0 d0, v1 d1, v2 d2, v3 d3, v4 d4, v5 d5, v6 d6, v7 d7, v8 d8, v9 d9, v10 d10, v11 d11, v12 d12, v13 d13, v14 d14];
+    16 => [v0 d0, v1 d1, v2 d2, v3 d3, v4 d4, v5 d5, v6 d6, v7 d7, v8 d8, v9 d9, v10 d10, v11 d11, v12 d12, v13 d13, v14 d14, v15 d15];
+}
+
+//--------------------------------------------------------------------------------
+
+mod test {
+    use super::*;
+    use ::complex::Complex;
+
+    // nesting, tuple structs, and a separate scalar type
+    #[derive(Debug, Copy, Clone, PartialEq, CustomPacked)]
+    #[custom_packed(root = "", scalar = "Tagged<f32>")]
+    struct TaggedVec(Complex<::faster::f32s>, ::faster::f32s);
+
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    struct Tagged<T>(Complex<T>, T);
+
+    // nested custom and array fields, next to a bare parameter of a different type
+    #[derive(Debug, Copy, Clone, PartialEq, CustomPacked)]
+    #[custom_packed(root = "")]
+    struct Labeled<V, W> { z: Complex<V>, a: [V; 2], w: W }
 
     #[test]
     fn custom_packed() {
@@ -604,4 +1623,279 @@ mod test {
         let cs = Complex::<f64s>::splat(Complex { real: 1.0, imag: 0.0 });
         assert_eq!(cs, Complex { real: f64s(1.0), imag: f64s(0.0) });
     }
+
+    #[test]
+    fn custom_packed_derive() {
+        use ::faster::{f32s, f64s, u64s};
+        let mut cs = Complex::<f64s>::default().replace(1, Complex { real: 2.0, imag: 3.0 });
+        assert_eq!(cs.extract(1), Complex { real: 2.0, imag: 3.0 });
+
+        *cs.as_packed_mut().1 .0 = f64s(5.0);
+        assert_eq!(cs.imag, f64s(5.0));
+
+        let scalar = Tagged(Complex { real: 1.0, imag: 2.0 }, 3.0);
+        let tagged = TaggedVec::splat(scalar);
+        assert_eq!(tagged.1, f32s(3.0));
+        assert_eq!(tagged.extract(0), scalar);
+
+        let scalar = Labeled { z: Complex { real: 1.0, imag: 2.0 }, a: [3.0, 4.0], w: 5 };
+        let labeled = Labeled::<f64s, u64s>::splat(scalar);
+        assert_eq!(labeled.w, u64s(5));
+        assert_eq!(labeled.extract(1), scalar);
+    }
+
+    #[test]
+    fn custom_packed_ops() {
+        use ::faster::f64s;
+        let z = Complex::<f64s>::splat(Complex { real: 1.0, imag: 2.0 });
+        let w = Complex::<f64s>::splat(Complex { real: 3.0, imag: -1.0 });
+        assert_eq!((z + w).extract(0), Complex { real: 4.0, imag: 1.0 });
+        assert_eq!((z - w).extract(0), Complex { real: -2.0, imag: 3.0 });
+        assert_eq!((-z).extract(0), Complex { real: -1.0, imag: -2.0 });
+        assert_eq!((z * w).extract(0), Complex { real: 5.0, imag: 5.0 });
+
+        let tagged = TaggedComplex::splat(Tagged(Complex { real: 1.0, imag: 2.0 }, 3.0));
+        assert_eq!((-(tagged + tagged)).extract(0), Tagged(Complex { real: -2.0, imag: -4.0 }, -6.0));
+
+        let p = Polar::<f64s>::splat(Polar { r: 2.0, theta: 1.0 });
+        assert_eq!((p * p / Polar::splat(Polar { r: 1.0, theta: 2.0 })).extract(0), Polar { r: 4.0, theta: 0.5 });
+    }
+
+    // the same, without the proc-macro (and with the scalars named by paths)
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    struct Polar<V> { r: V, theta: V }
+
+    custom_packed! {
+        impl<V> Polar<V> { r: V, theta: V } => ::packed::test::Polar<V::Scalar>
+    }
+
+    #[derive(Debug, Copy, Clone, PartialEq)]
+    struct TaggedComplex(Complex<::faster::f64s>, ::faster::f64s);
+
+    custom_packed! {
+        impl TaggedComplex { 0: Complex<::faster::f64s>, 1: ::faster::f64s } => self::Tagged<f64>
+    }
+
+    custom_packed_ops! {
+        impl TaggedComplex: Add, Neg
+    }
+
+    custom_packed_ops! {
+        impl<V> Polar<V>: Mul, Div
+    }
+
+    #[test]
+    fn custom_packed_macro() {
+        use ::faster::f64s;
+        let mut ps = Polar::<f64s>::splat(Polar { r: 1.0, theta: 0.5 });
+        assert_eq!(ps.extract(0), Polar { r: 1.0, theta: 0.5 });
+
+        *ps.as_packed_mut().1 .0 = f64s(2.0);
+        assert_eq!(ps.theta, f64s(2.0));
+
+        let tagged = TaggedComplex::splat(Tagged(Complex { real: 1.0, imag: 2.0 }, 3.0));
+        assert_eq!(tagged.0.imag, f64s(2.0));
+        assert_eq!(tagged.extract(0), Tagged(Complex { real: 1.0, imag: 2.0 }, 3.0));
+    }
+
+    #[test]
+    fn tuples() {
+        use ::faster::{f32s, f64s};
+
+        let pair = <(f64s, f64s)>::splat((1.0, 2.0));
+        assert_eq!(pair, (f64s(1.0), f64s(2.0)));
+        assert_eq!(pair.replace(0, (3.0, 4.0)).extract(0), (3.0, 4.0));
+
+        let xs = vec![1.0; f64s::WIDTH];
+        let ys = vec![2.0; f64s::WIDTH];
+        let mut out = vec![0.0; f64s::WIDTH];
+        let (x, y) = <(f64s, f64s)>::load_unaligned(vlist![&xs[..], &ys[..]]);
+        (y, x).0.store_unaligned(&mut out[..]);
+        assert_eq!(out, ys);
+
+        type Mixed = <(f32, f64) as Packable>::Vector;
+        assert_eq!(Mixed::WIDTH, ::std::cmp::min(f32s::WIDTH, f64s::WIDTH));
+        assert_eq!(Mixed::splat((1.0, 2.0)).extract(0), (1.0, 2.0));
+
+        assert_eq!(<(f64s,)>::splat((5.0,)).0, f64s(5.0));
+        assert_eq!(Cons::from((1, 'a')), vlist![1, 'a']);
+        assert_eq!(vlist![1, 'a', "b"].into_tuple(), (1, 'a', "b"));
+    }
+
+    #[test]
+    fn arrays() {
+        use ::faster::{f32s, f64s};
+
+        let xs: Vec<f64> = (0..f64s::WIDTH).map(|x| x as f64).collect();
+        let ys = vec![1.0; f64s::WIDTH];
+        let zs = vec![2.0; f64s::WIDTH];
+        let xyz = <[f64s; 3]>::load_unaligned([&xs[..], &ys[..], &zs[..]]);
+        assert_eq!(xyz.extract(1), [1.0, 1.0, 2.0]);
+        assert_eq!(xyz.replace(0, [5.0, 6.0, 7.0]).extract(0), [5.0, 6.0, 7.0]);
+
+        let mut out = vec![vec![0.0; f64s::WIDTH]; 3];
+        {
+            let (a, rest) = out.split_at_mut(1);
+            let (b, c) = rest.split_at_mut(1);
+            xyz.store_unaligned([&mut a[0][..], &mut b[0][..], &mut c[0][..]]);
+        }
+        assert_eq!(out, vec![xs, ys, zs]);
+
+        let mut pair = <[f32s; 2] as Packed>::default();
+        *pair.as_packed_mut()[1] = f32s(1.0);
+        assert_eq!(pair, [f32s(0.0), f32s(1.0)]);
+        assert_eq!(<[f32; 4] as Packable>::Vector::splat([3.0; 4])[2], f32s(3.0));
+    }
+
+    #[repr(align(64))]
+    struct Aligned<T>(T);
+
+    #[test]
+    fn aligned() {
+        use ::faster::f64s;
+
+        let xs = Aligned([1.0; 16]);
+        let ys = Aligned([2.0; 16]);
+        let mut out = Aligned([0.0; 16]);
+
+        let v = <VList![f64s, f64s] as Packed>::load_aligned(vlist![&xs.0[..], &ys.0[..]]);
+        assert_eq!(v, vlist![f64s(1.0), f64s(2.0)]);
+        let c = <Complex<f64s> as Packed>::load_aligned(vlist![&xs.0[..], &ys.0[..]]);
+        assert_eq!(c.extract(0), Complex { real: 1.0, imag: 2.0 });
+
+        // (through the trait, since the std vectors have inherent methods of the same names)
+        unsafe { Packed::store_aligned_unchecked(v.0 + v.1 .0, &mut out.0[..]) };
+        assert_eq!(out.0[0], 3.0);
+
+        Packed::store_aligned(f64s(4.0), &mut out.0[..]);
+        assert_eq!(out.0[0], 4.0);
+
+        let mut out2 = Aligned([0.0; 16]);
+        Packed::store_aligned(v, vlist![&mut out.0[..], &mut out2.0[..]]);
+        assert_eq!((out.0[0], out2.0[0]), (1.0, 2.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "load_aligned")]
+    fn misaligned() {
+        use ::faster::f64s;
+
+        let xs = Aligned([1.0; 16]);
+        let ys = Aligned([2.0; 16]);
+        // only the second slice is misaligned
+        <VList![f64s, f64s]>::load_aligned(vlist![&xs.0[..], &ys.0[1..]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "store_aligned")]
+    fn misaligned_store() {
+        use ::faster::f64s;
+
+        let mut out = Aligned([0.0; 16]);
+        Packed::store_aligned(f64s::splat(1.0), &mut out.0[1..]);
+    }
+
+    #[test]
+    #[should_panic(expected = "store_aligned")]
+    fn misaligned_store_vlist() {
+        use ::faster::f64s;
+
+        let mut xs = Aligned([0.0; 16]);
+        let mut ys = Aligned([0.0; 16]);
+        // only the second slice is misaligned
+        Packed::store_aligned(vlist![f64s::splat(1.0), f64s::splat(2.0)], vlist![&mut xs.0[..], &mut ys.0[1..]]);
+    }
+
+    #[test]
+    fn partial() {
+        use ::faster::{f32s, f64s};
+
+        let xs = [1.0, 2.0];
+        let v = f64s::load_partial(&xs[..], -1.0);
+        assert_eq!(v.extract(1), 2.0);
+        assert_eq!(v.extract(f64s::WIDTH - 1), if f64s::WIDTH > 2 { -1.0 } else { 2.0 });
+
+        let mut out = [0.0; 1];
+        v.store_partial(&mut out[..]);
+        assert_eq!(out, [1.0]);
+
+        // each field is considered separately
+        let ys = [3.0; 1];
+        let z = Complex::<f64s>::load_partial(vlist![&xs[..], &ys[..]], Complex { real: 0.0, imag: 0.0 });
+        assert_eq!(z.extract(0), Complex { real: 1.0, imag: 3.0 });
+        assert_eq!(z.extract(1), Complex { real: 2.0, imag: 0.0 });
+
+        let mut reals = [0.0; 3];
+        let mut imags = [0.0; 3];
+        z.store_partial(vlist![&mut reals[..1], &mut imags[..]]);
+        assert_eq!(reals, [1.0, 0.0, 0.0]);
+        assert_eq!(&imags[..2], &[3.0, 0.0]);
+
+        // a full slice is just an unaligned load
+        let zs: Vec<f32> = (0..f32s::WIDTH + 1).map(|x| x as f32).collect();
+        let arrays = <[f32s; 2]>::load_partial([&zs[..], &zs[1..]], [9.0, 9.0]);
+        assert_eq!(arrays.extract(f32s::WIDTH - 1), [(f32s::WIDTH - 1) as f32, f32s::WIDTH as f32]);
+    }
+
+    #[test]
+    fn primitive_packed() {
+        use ::faster::{f32s, i16s, u8s};
+        let xs: Vec<f32> = (0..f32s::WIDTH).map(|x| x as f32).collect();
+        // (the std vectors have inherent methods with the same names, so these spell out the trait)
+        let v = <f32s as Packed>::load_unaligned(&xs[..]);
+        assert_eq!(Packed::extract(&v, 1), 1.0);
+        assert_eq!(Packed::extract(&Packed::replace(v, 1, 5.0), 1), 5.0);
+        assert_eq!(<f32 as Packable>::Vector::WIDTH, f32s::WIDTH);
+
+        let ones = <i16s as Packed>::splat(-1);
+        assert_eq!(PrimitivePacked::product(&ones), if i16s::WIDTH % 2 == 0 { 1 } else { -1 });
+
+        let mut out = vec![0; u8s::WIDTH];
+        Packed::store_unaligned(<u8s as Packed>::splat(7), &mut out[..]);
+        assert!(out.iter().all(|&x| x == 7));
+    }
+
+    #[test]
+    fn mixed_width_packable() {
+        use ::std::cmp::min;
+        use ::faster::{f32s, f64s, u8s, u32s};
+
+        type Fs = <VList![f32, f64] as Packable>::Vector;
+        assert_eq!(Fs::WIDTH, min(f32s::WIDTH, f64s::WIDTH));
+        let vlist_pat![a, b] = Fs::splat(vlist![1.0, 2.0]);
+        assert_eq!((a.extract(0), b.extract(0)), (1.0, 2.0));
+
+        type Us = <VList![u8, u32, u8] as Packable>::Vector;
+        assert_eq!(Us::WIDTH, min(u8s::WIDTH, u32s::WIDTH));
+    }
+
+    #[test]
+    fn gather_scatter() {
+        use ::faster::{f64s, u64s};
+        use ::std::panic::{self, AssertUnwindSafe};
+
+        let n = 3 * f64s::WIDTH;
+        let mut reals: Vec<f64> = (0..n).map(|i| i as f64).collect();
+        let mut imags: Vec<f64> = reals.iter().map(|&x| -x).collect();
+
+        // every other element, backwards
+        let mut indices = u64s::splat(0);
+        for i in 0..u64s::WIDTH {
+            indices = indices.replace(i, (n - 1 - 2 * i) as u64);
+        }
+        let z = Complex::<f64s>::gather(vlist![&reals[..], &imags[..]], indices);
+        assert_eq!(z.extract(1), Complex { real: (n - 3) as f64, imag: -((n - 3) as f64) });
+
+        z.conj().scatter(vlist![&mut reals[..], &mut imags[..]], indices);
+        assert_eq!((reals[n - 3], imags[n - 3]), ((n - 3) as f64, (n - 3) as f64));
+        assert_eq!((reals[n - 2], imags[n - 2]), ((n - 2) as f64, -((n - 2) as f64)));
+
+        // an index past the end of the shortest slice writes nothing at all
+        let before = (reals.clone(), imags.clone());
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            z.scatter(vlist![&mut reals[..], &mut imags[..n - 1]], indices);
+        }));
+        assert!(result.is_err());
+        assert_eq!((reals, imags), before);
+    }
 }
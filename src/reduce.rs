@@ -0,0 +1,325 @@
+use ::vlist::{Cons, Nil};
+use ::packed::{Packed, PrimitivePacked, PrimitiveIntPacked, CustomPacked};
+use ::intrinsics::{simd_shuffle2, simd_shuffle4, simd_shuffle8};
+use ::intrinsics::{simd_shuffle16, simd_shuffle32, simd_shuffle64};
+use ::faster::vecs::{f32x4, f32x8, f32x16, f64x2, f64x4, f64x8};
+use ::faster::vecs::{i8x16, i8x32, i8x64, i16x8, i16x16, i16x32};
+use ::faster::vecs::{i32x4, i32x8, i32x16, i64x2, i64x4, i64x8};
+use ::faster::vecs::{u8x16, u8x32, u8x64, u16x8, u16x16, u16x32};
+use ::faster::vecs::{u32x4, u32x8, u32x16, u64x2, u64x4, u64x8};
+use ::std::simd::{f32x2, i8x2, i8x4, i8x8, i16x2, i16x4, i32x2};
+use ::std::simd::{u8x2, u8x4, u8x8, u16x2, u16x4, u32x2};
+
+/// The sum across lanes, for any `Packed` type whose primitive fields can be summed.
+///
+/// Each field is summed separately, so e.g. the sum of a `Complex<f64s>` is the
+/// `Complex<f64>` that you'd expect.  (there is deliberately no such thing for `product`;
+/// see `PrimitivePacked`)
+pub trait PackedSum: Packed {
+    fn sum_lanes(self) -> Self::Scalar;
+}
+
+macro_rules! impl_primitive_packed_sum {
+    ($($Vector:ident),*) => {$(
+        impl PackedSum for $Vector {
+            #[inline(always)]
+            fn sum_lanes(self) -> Self::Scalar { PrimitivePacked::sum(&self) }
+        }
+    )*};
+}
+
+impl_primitive_packed_sum!{
+    f32x2, f32x4, f32x8, f32x16, f64x2, f64x4, f64x8,
+    i8x2, i8x4, i8x8, i8x16, i8x32, i8x64,
+    i16x2, i16x4, i16x8, i16x16, i16x32,
+    i32x2, i32x4, i32x8, i32x16, i64x2, i64x4, i64x8,
+    u8x2, u8x4, u8x8, u8x16, u8x32, u8x64,
+    u16x2, u16x4, u16x8, u16x16, u16x32,
+    u32x2, u32x4, u32x8, u32x16, u64x2, u64x4, u64x8
+}
+
+impl<V> PackedSum for Cons<V, Nil>
+where
+    V: PackedSum,
+{
+    #[inline(always)]
+    fn sum_lanes(self) -> Self::Scalar {
+        vlist![self.0.sum_lanes()]
+    }
+}
+
+impl<V, Rest> PackedSum for Cons<V, Rest>
+where
+    V: PackedSum,
+    Rest: PackedSum<Width = V::Width>,
+{
+    #[inline(always)]
+    fn sum_lanes(self) -> Self::Scalar {
+        let vlist_pat![v, ...rest] = self;
+        vlist![v.sum_lanes(), ...rest.sum_lanes()]
+    }
+}
+
+impl<T> PackedSum for T
+where
+    T: CustomPacked,
+    T::BaseVector: PackedSum,
+{
+    #[inline(always)]
+    fn sum_lanes(self) -> Self::Scalar {
+        T::scalar_from_base(T::vector_into_base(self).sum_lanes())
+    }
+}
+
+macro_rules! impl_packed_sum_for_arrays {
+    ($($N:expr => [$($v:ident),*];)*) => {$(
+        impl<V> PackedSum for [V; $N]
+        where
+            V: PackedSum,
+        {
+            #[inline(always)]
+            fn sum_lanes(self) -> Self::Scalar {
+                let [$($v),*] = self;
+                [$($v.sum_lanes()),*]
+            }
+        }
+    )*};
+}
+
+impl_packed_sum_for_arrays!{
+    1 => [v0];
+    2 => [v0, v1];
+    3 => [v0, v1, v2];
+    4 => [v0, v1, v2, v3];
+    5 => [v0, v1, v2, v3, v4];
+    6 => [v0, v1, v2, v3, v4, v5];
+    7 => [v0, v1, v2, v3, v4, v5, v6];
+    8 => [v0, v1, v2, v3, v4, v5, v6, v7];
+    9 => [v0, v1, v2, v3, v4, v5, v6, v7, v8];
+    10 => [v0, v1, v2, v3, v4, v5, v6, v7, v8, v9];
+    11 => [v0, v1, v2, v3, v4, v5, v6, v7, v8, v9, v10];
+    12 => [v0, v1, v2, v3, v4, v5, v6, v7, v8, v9, v10, v11];
+    13 => [v0, v1, v2, v3, v4, v5, v6, v7, v8, v9, v10, v11, v12];
+    14 => [v0, v1, v2, v3, v4, v5, v6, v7, v8, v9, v10, v11, v12, v13];
+    15 => [v0, v1, v2, v3, v4, v5, v6, v7, v8, v9, v10, v11, v12, v13, v14];
+    16 => [v0, v1, v2, v3, v4, v5, v6, v7, v8, v9, v10, v11, v12, v13, v14, v15];
+}
+
+// Horizontal reductions are done in `log2(WIDTH)` steps.  At each step, every lane is
+// combined with the lane `d` away from it (`SWAP_N_d` swaps such pairs), for
+// `d = WIDTH/2, ..., 2, 1`; afterwards, every lane holds the reduction of the whole vector.
+macro_rules! reduce {
+    ($vector:expr, $op:expr; $shuffle:ident: [$($SWAP:ident),*]) => {{
+        let op = $op;
+        let mut v = $vector;
+        $( v = op(v, unsafe { $shuffle(v, v, $SWAP) }); )*
+        v.extract(0)
+    }};
+}
+
+macro_rules! impl_primitive_reductions {
+    ($($shuffle:ident, $swaps:tt: [$($Vector:ident),*];)*) => {$($(
+        impl PrimitivePacked for $Vector {
+            #[inline(always)]
+            fn sum(&self) -> Self::Scalar {
+                reduce!(*self, |a: Self, b: Self| a + b; $shuffle: $swaps)
+            }
+
+            #[inline(always)]
+            fn product(&self) -> Self::Scalar {
+                reduce!(*self, |a: Self, b: Self| a * b; $shuffle: $swaps)
+            }
+
+            #[inline(always)]
+            fn min(&self) -> Self::Scalar {
+                reduce!(*self, |a: Self, b: Self| a.lt(b).select(a, b); $shuffle: $swaps)
+            }
+
+            #[inline(always)]
+            fn max(&self) -> Self::Scalar {
+                reduce!(*self, |a: Self, b: Self| a.gt(b).select(a, b); $shuffle: $swaps)
+            }
+        }
+    )*)*};
+}
+
+macro_rules! impl_primitive_int_reductions {
+    ($($shuffle:ident, $swaps:tt: [$($Vector:ident),*];)*) => {$($(
+        impl PrimitiveIntPacked for $Vector {
+            #[inline(always)]
+            fn and(&self) -> Self::Scalar {
+                reduce!(*self, |a: Self, b: Self| a & b; $shuffle: $swaps)
+            }
+
+            #[inline(always)]
+            fn or(&self) -> Self::Scalar {
+                reduce!(*self, |a: Self, b: Self| a | b; $shuffle: $swaps)
+            }
+
+            #[inline(always)]
+            fn xor(&self) -> Self::Scalar {
+                reduce!(*self, |a: Self, b: Self| a ^ b; $shuffle: $swaps)
+            }
+        }
+    )*)*};
+}
+
+impl_primitive_reductions!{
+    simd_shuffle2, [SWAP_2_1]: [f32x2, f64x2, i8x2, u8x2, i16x2, u16x2, i32x2, u32x2, i64x2, u64x2];
+    simd_shuffle4, [SWAP_4_2, SWAP_4_1]: [f32x4, f64x4, i8x4, u8x4, i16x4, u16x4, i32x4, u32x4, i64x4, u64x4];
+    simd_shuffle8, [SWAP_8_4, SWAP_8_2, SWAP_8_1]: [f32x8, f64x8, i8x8, u8x8, i16x8, u16x8, i32x8, u32x8, i64x8, u64x8];
+    simd_shuffle16, [SWAP_16_8, SWAP_16_4, SWAP_16_2, SWAP_16_1]: [f32x16, i8x16, u8x16, i16x16, u16x16, i32x16, u32x16];
+    simd_shuffle32, [SWAP_32_16, SWAP_32_8, SWAP_32_4, SWAP_32_2, SWAP_32_1]: [i8x32, u8x32, i16x32, u16x32];
+    simd_shuffle64, [SWAP_64_32, SWAP_64_16, SWAP_64_8, SWAP_64_4, SWAP_64_2, SWAP_64_1]: [i8x64, u8x64];
+}
+
+impl_primitive_int_reductions!{
+    simd_shuffle2, [SWAP_2_1]: [i8x2, u8x2, i16x2, u16x2, i32x2, u32x2, i64x2, u64x2];
+    simd_shuffle4, [SWAP_4_2, SWAP_4_1]: [i8x4, u8x4, i16x4, u16x4, i32x4, u32x4, i64x4, u64x4];
+    simd_shuffle8, [SWAP_8_4, SWAP_8_2, SWAP_8_1]: [i8x8, u8x8, i16x8, u16x8, i32x8, u32x8, i64x8, u64x8];
+    simd_shuffle16, [SWAP_16_8, SWAP_16_4, SWAP_16_2, SWAP_16_1]: [i8x16, u8x16, i16x16, u16x16, i32x16, u32x16];
+    simd_shuffle32, [SWAP_32_16, SWAP_32_8, SWAP_32_4, SWAP_32_2, SWAP_32_1]: [i8x32, u8x32, i16x32, u16x32];
+    simd_shuffle64, [SWAP_64_32, SWAP_64_16, SWAP_64_8, SWAP_64_4, SWAP_64_2, SWAP_64_1]: [i8x64, u8x64];
+}
+
+const SWAP_2_1: [u32; 2] = [1, 0];
+
+const SWAP_4_2: [u32; 4] = [2, 3, 0, 1];
+const SWAP_4_1: [u32; 4] = [1, 0, 3, 2];
+
+const SWAP_8_4: [u32; 8] = [4, 5, 6, 7, 0, 1, 2, 3];
+const SWAP_8_2: [u32; 8] = [2, 3, 0, 1, 6, 7, 4, 5];
+const SWAP_8_1: [u32; 8] = [1, 0, 3, 2, 5, 4, 7, 6];
+
+const SWAP_16_8: [u32; 16] = [8, 9, 10, 11, 12, 13, 14, 15, 0, 1, 2, 3, 4, 5, 6, 7];
+const SWAP_16_4: [u32; 16] = [4, 5, 6, 7, 0, 1, 2, 3, 12, 13, 14, 15, 8, 9, 10, 11];
+const SWAP_16_2: [u32; 16] = [2, 3, 0, 1, 6, 7, 4, 5, 10, 11, 8, 9, 14, 15, 12, 13];
+const SWAP_16_1: [u32; 16] = [1, 0, 3, 2, 5, 4, 7, 6, 9, 8, 11, 10, 13, 12, 15, 14];
+
+const SWAP_32_16: [u32; 32] = [
+    16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31,
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+];
+const SWAP_32_8: [u32; 32] = [
+    8, 9, 10, 11, 12, 13, 14, 15, 0, 1, 2, 3, 4, 5, 6, 7,
+    24, 25, 26, 27, 28, 29, 30, 31, 16, 17, 18, 19, 20, 21, 22, 23,
+];
+const SWAP_32_4: [u32; 32] = [
+    4, 5, 6, 7, 0, 1, 2, 3, 12, 13, 14, 15, 8, 9, 10, 11,
+    20, 21, 22, 23, 16, 17, 18, 19, 28, 29, 30, 31, 24, 25, 26, 27,
+];
+const SWAP_32_2: [u32; 32] = [
+    2, 3, 0, 1, 6, 7, 4, 5, 10, 11, 8, 9, 14, 15, 12, 13,
+    18, 19, 16, 17, 22, 23, 20, 21, 26, 27, 24, 25, 30, 31, 28, 29,
+];
+const SWAP_32_1: [u32; 32] = [
+    1, 0, 3, 2, 5, 4, 7, 6, 9, 8, 11, 10, 13, 12, 15, 14,
+    17, 16, 19, 18, 21, 20, 23, 22, 25, 24, 27, 26, 29, 28, 31, 30,
+];
+
+const SWAP_64_32: [u32; 64] = [
+    32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47,
+    48, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63,
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+    16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31,
+];
+const SWAP_64_16: [u32; 64] = [
+    16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31,
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+    48, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63,
+    32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47,
+];
+const SWAP_64_8: [u32; 64] = [
+    8, 9, 10, 11, 12, 13, 14, 15, 0, 1, 2, 3, 4, 5, 6, 7,
+    24, 25, 26, 27, 28, 29, 30, 31, 16, 17, 18, 19, 20, 21, 22, 23,
+    40, 41, 42, 43, 44, 45, 46, 47, 32, 33, 34, 35, 36, 37, 38, 39,
+    56, 57, 58, 59, 60, 61, 62, 63, 48, 49, 50, 51, 52, 53, 54, 55,
+];
+const SWAP_64_4: [u32; 64] = [
+    4, 5, 6, 7, 0, 1, 2, 3, 12, 13, 14, 15, 8, 9, 10, 11,
+    20, 21, 22, 23, 16, 17, 18, 19, 28, 29, 30, 31, 24, 25, 26, 27,
+    36, 37, 38, 39, 32, 33, 34, 35, 44, 45, 46, 47, 40, 41, 42, 43,
+    52, 53, 54, 55, 48, 49, 50, 51, 60, 61, 62, 63, 56, 57, 58, 59,
+];
+const SWAP_64_2: [u32; 64] = [
+    2, 3, 0, 1, 6, 7, 4, 5, 10, 11, 8, 9, 14, 15, 12, 13,
+    18, 19, 16, 17, 22, 23, 20, 21, 26, 27, 24, 25, 30, 31, 28, 29,
+    34, 35, 32, 33, 38, 39, 36, 37, 42, 43, 40, 41, 46, 47, 44, 45,
+    50, 51, 48, 49, 54, 55, 52, 53, 58, 59, 56, 57, 62, 63, 60, 61,
+];
+const SWAP_64_1: [u32; 64] = [
+    1, 0, 3, 2, 5, 4, 7, 6, 9, 8, 11, 10, 13, 12, 15, 14,
+    17, 16, 19, 18, 21, 20, 23, 22, 25, 24, 27, 26, 29, 28, 31, 30,
+    33, 32, 35, 34, 37, 36, 39, 38, 41, 40, 43, 42, 45, 44, 47, 46,
+    49, 48, 51, 50, 53, 52, 55, 54, 57, 56, 59, 58, 61, 60, 63, 62,
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::complex::Complex;
+    use ::faster::{f32s, f64s, i8s, i32s, u16s, u64s};
+
+    // (pseudo-random, but with lanes spread over the whole vector)
+    fn lanes<V: Packed, F: Fn(usize) -> V::Scalar>(f: F) -> V {
+        (0..V::WIDTH).fold(V::default(), |v, i| v.replace(i, f(i)))
+    }
+
+    fn scalars<V: Packed>(v: &V) -> Vec<V::Scalar> {
+        v.scalar_reduce(vec![], |mut acc, x| { acc.push(x); acc })
+    }
+
+    // (the std vectors have inherent methods with the same names, so these spell out the trait)
+    macro_rules! check_reductions {
+        ($v:expr, $zero:expr, $one:expr, $add:expr, $mul:expr) => {{
+            let v = $v;
+            let xs = scalars(&v);
+            assert_eq!(PrimitivePacked::sum(&v), xs.iter().fold($zero, |a, &b| $add(a, b)));
+            assert_eq!(PrimitivePacked::product(&v), xs.iter().fold($one, |a, &b| $mul(a, b)));
+            assert_eq!(PrimitivePacked::min(&v), xs.iter().cloned().fold(xs[0], |a, b| if b < a { b } else { a }));
+            assert_eq!(PrimitivePacked::max(&v), xs.iter().cloned().fold(xs[0], |a, b| if b > a { b } else { a }));
+        }};
+    }
+
+    #[test]
+    fn floats() {
+        // (small integers, so that the order of the additions doesn't matter)
+        check_reductions!(lanes::<f32s, _>(|i| ((i * 7) % 5) as f32 - 2.0), 0.0, 1.0, |a, b| a + b, |a, b| a * b);
+        check_reductions!(lanes::<f64s, _>(|i| ((i * 3) % 4) as f64 + 0.5), 0.0, 1.0, |a, b| a + b, |a, b| a * b);
+        check_reductions!(lanes::<f32x2, _>(|i| -(i as f32)), 0.0, 1.0, |a, b| a + b, |a, b| a * b);
+    }
+
+    #[test]
+    fn ints() {
+        let v = lanes::<i8s, _>(|i| (i as i8).wrapping_mul(37));
+        check_reductions!(v, 0, 1, i8::wrapping_add, i8::wrapping_mul);
+        let xs = scalars(&v);
+        assert_eq!(PrimitiveIntPacked::and(&v), xs.iter().fold(!0, |a, &b| a & b));
+        assert_eq!(PrimitiveIntPacked::or(&v), xs.iter().fold(0, |a, &b| a | b));
+        assert_eq!(PrimitiveIntPacked::xor(&v), xs.iter().fold(0, |a, &b| a ^ b));
+
+        let v = lanes::<u16s, _>(|i| 1 << (i % 16));
+        check_reductions!(v, 0, 1, u16::wrapping_add, u16::wrapping_mul);
+        let xs = scalars(&v);
+        assert_eq!(PrimitiveIntPacked::or(&v), xs.iter().fold(0, |a, &b| a | b));
+        assert_eq!(PrimitiveIntPacked::xor(&v), xs.iter().fold(0, |a, &b| a ^ b));
+
+        check_reductions!(lanes::<i32s, _>(|i| 1000 - 300 * i as i32), 0, 1, i32::wrapping_add, i32::wrapping_mul);
+        check_reductions!(lanes::<u64s, _>(|i| !0 - i as u64), 0, 1, u64::wrapping_add, u64::wrapping_mul);
+        check_reductions!(lanes::<u8x4, _>(|i| 200 + i as u8), 0, 1, u8::wrapping_add, u8::wrapping_mul);
+    }
+
+    #[test]
+    fn sum_lanes() {
+        let z = Complex {
+            real: lanes::<f64s, _>(|i| i as f64),
+            imag: f64s::splat(-1.0),
+        };
+        let n = f64s::WIDTH as f64;
+        assert_eq!(z.sum_lanes(), Complex { real: n * (n - 1.0) / 2.0, imag: -n });
+
+        let v = <VList![f32s, i32s]>::splat(vlist![0.5, 3]);
+        assert_eq!(v.sum_lanes(), vlist![0.5 * f32s::WIDTH as f32, 3 * i32s::WIDTH as i32]);
+        assert_eq!(<[u16s; 2]>::splat([1, 2]).sum_lanes(), [u16s::WIDTH as u16, 2 * u16s::WIDTH as u16]);
+    }
+}
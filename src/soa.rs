@@ -0,0 +1,577 @@
+use ::std::iter::FromIterator;
+use ::vlist::{Cons, Nil};
+use ::packed::{Packed, PackedGats, Packable, CustomPacked};
+use ::aligned::AlignedVec;
+use ::iter::SimdIterator;
+use ::std::simd::{f32x2, i8x2, i8x4, i8x8, i16x2, i16x4, i32x2};
+use ::std::simd::{u8x2, u8x4, u8x8, u16x2, u16x4, u32x2};
+use ::faster::vecs::{f32x4, f32x8, f32x16, f64x2, f64x4, f64x8};
+use ::faster::vecs::{i8x16, i8x32, i8x64, i16x8, i16x16, i16x32};
+use ::faster::vecs::{i32x4, i32x8, i32x16, i64x2, i64x4, i64x8};
+use ::faster::vecs::{u8x16, u8x32, u8x64, u16x8, u16x16, u16x32};
+use ::faster::vecs::{u32x4, u32x8, u32x16, u64x2, u64x4, u64x8};
+
+/// Owned storage for the scalars of a `Packed` type, with one `AlignedVec` per primitive field.
+///
+/// Implemented on:
+/// * primitive vectors, whose columns are a single `AlignedVec`
+/// * VLists thereof, whose columns are a VList of the columns
+/// * `CustomPacked` types, which use the columns of their `BaseVector`
+///
+/// These are all associated functions that take the columns as an argument;
+/// `SoaVec` wraps them up into something nicer.
+pub trait PackedColumns: Packed {
+    type Columns: Default + Clone;
+
+    /// The number of scalars.  (all columns have the same length)
+    fn len(columns: &Self::Columns) -> usize;
+    fn reserve(columns: &mut Self::Columns, additional: usize);
+    fn push(columns: &mut Self::Columns, value: Self::Scalar);
+    fn pop(columns: &mut Self::Columns) -> Option<Self::Scalar>;
+    fn truncate(columns: &mut Self::Columns, len: usize);
+
+    /// Panics if `idx` is out of bounds.
+    fn get(columns: &Self::Columns, idx: usize) -> Self::Scalar;
+    /// Panics if `idx` is out of bounds.
+    fn set(columns: &mut Self::Columns, idx: usize, value: Self::Scalar);
+
+    fn as_slices<'a>(columns: &'a Self::Columns) -> <Self as PackedGats<'a>>::ScalarSlice;
+    fn as_slices_mut<'a>(columns: &'a mut Self::Columns) -> <Self as PackedGats<'a>>::ScalarSliceMut;
+
+    /// Aligned load of the `chunk`th vector, which may extend into the padding.
+    ///
+    /// Panics if the chunk starts past the end of the padding.
+    fn load_chunk(columns: &Self::Columns, chunk: usize) -> Self;
+
+    /// Aligned store of the `chunk`th vector.  Lanes that fall in the padding are discarded.
+    ///
+    /// Panics if the chunk starts past the end of the padding.
+    fn store_chunk(self, columns: &mut Self::Columns, chunk: usize);
+}
+
+macro_rules! impl_primitive_packed_columns {
+    ($($Scalar:ident: [$($Vector:ident),*];)*) => {$($(
+        impl PackedColumns for $Vector {
+            type Columns = AlignedVec<$Scalar>;
+
+            #[inline(always)]
+            fn len(columns: &Self::Columns) -> usize { columns.len() }
+
+            #[inline(always)]
+            fn reserve(columns: &mut Self::Columns, additional: usize) { columns.reserve(additional) }
+
+            #[inline(always)]
+            fn push(columns: &mut Self::Columns, value: $Scalar) { columns.push(value) }
+
+            #[inline(always)]
+            fn pop(columns: &mut Self::Columns) -> Option<$Scalar> { columns.pop() }
+
+            #[inline(always)]
+            fn truncate(columns: &mut Self::Columns, len: usize) { columns.truncate(len) }
+
+            #[inline(always)]
+            fn get(columns: &Self::Columns, idx: usize) -> $Scalar { columns[idx] }
+
+            #[inline(always)]
+            fn set(columns: &mut Self::Columns, idx: usize, value: $Scalar) { columns[idx] = value }
+
+            #[inline(always)]
+            fn as_slices<'a>(columns: &'a Self::Columns) -> &'a [$Scalar] { columns }
+
+            #[inline(always)]
+            fn as_slices_mut<'a>(columns: &'a mut Self::Columns) -> &'a mut [$Scalar] { columns }
+
+            #[inline(always)]
+            fn load_chunk(columns: &Self::Columns, chunk: usize) -> Self {
+                Self::load_aligned(&columns.padded()[chunk * Self::WIDTH..])
+            }
+
+            #[inline(always)]
+            fn store_chunk(self, columns: &mut Self::Columns, chunk: usize) {
+                self.store_aligned(&mut columns.padded_mut()[chunk * Self::WIDTH..]);
+                if (chunk + 1) * Self::WIDTH > columns.len() {
+                    columns.clear_padding();
+                }
+            }
+        }
+    )*)*};
+}
+
+impl_primitive_packed_columns!{
+    f32: [f32x2, f32x4, f32x8, f32x16];
+    f64: [f64x2, f64x4, f64x8];
+    i8: [i8x2, i8x4, i8x8, i8x16, i8x32, i8x64];
+    u8: [u8x2, u8x4, u8x8, u8x16, u8x32, u8x64];
+    i16: [i16x2, i16x4, i16x8, i16x16, i16x32];
+    u16: [u16x2, u16x4, u16x8, u16x16, u16x32];
+    i32: [i32x2, i32x4, i32x8, i32x16];
+    u32: [u32x2, u32x4, u32x8, u32x16];
+    i64: [i64x2, i64x4, i64x8];
+    u64: [u64x2, u64x4, u64x8];
+}
+
+impl<V> PackedColumns for Cons<V, Nil>
+where
+    V: PackedColumns,
+{
+    type Columns = Cons<V::Columns, Nil>;
+
+    #[inline(always)]
+    fn len(columns: &Self::Columns) -> usize {
+        V::len(&columns.0)
+    }
+
+    #[inline(always)]
+    fn reserve(columns: &mut Self::Columns, additional: usize) {
+        V::reserve(&mut columns.0, additional)
+    }
+
+    #[inline(always)]
+    fn push(columns: &mut Self::Columns, value: Self::Scalar) {
+        let vlist_pat![value] = value;
+        V::push(&mut columns.0, value)
+    }
+
+    #[inline(always)]
+    fn pop(columns: &mut Self::Columns) -> Option<Self::Scalar> {
+        V::pop(&mut columns.0).map(|v| vlist![v])
+    }
+
+    #[inline(always)]
+    fn truncate(columns: &mut Self::Columns, len: usize) {
+        V::truncate(&mut columns.0, len)
+    }
+
+    #[inline(always)]
+    fn get(columns: &Self::Columns, idx: usize) -> Self::Scalar {
+        vlist![V::get(&columns.0, idx)]
+    }
+
+    #[inline(always)]
+    fn set(columns: &mut Self::Columns, idx: usize, value: Self::Scalar) {
+        let vlist_pat![value] = value;
+        V::set(&mut columns.0, idx, value)
+    }
+
+    #[inline(always)]
+    fn as_slices<'a>(columns: &'a Self::Columns) -> <Self as PackedGats<'a>>::ScalarSlice {
+        vlist![V::as_slices(&columns.0)]
+    }
+
+    #[inline(always)]
+    fn as_slices_mut<'a>(columns: &'a mut Self::Columns) -> <Self as PackedGats<'a>>::ScalarSliceMut {
+        vlist![V::as_slices_mut(&mut columns.0)]
+    }
+
+    #[inline(always)]
+    fn load_chunk(columns: &Self::Columns, chunk: usize) -> Self {
+        vlist![V::load_chunk(&columns.0, chunk)]
+    }
+
+    #[inline(always)]
+    fn store_chunk(self, columns: &mut Self::Columns, chunk: usize) {
+        let vlist_pat![v] = self;
+        v.store_chunk(&mut columns.0, chunk)
+    }
+}
+
+impl<V, Rest> PackedColumns for Cons<V, Rest>
+where
+    V: PackedColumns,
+    Rest: PackedColumns<Width = V::Width>,
+{
+    type Columns = Cons<V::Columns, Rest::Columns>;
+
+    #[inline(always)]
+    fn len(columns: &Self::Columns) -> usize {
+        V::len(&columns.0)
+    }
+
+    #[inline(always)]
+    fn reserve(columns: &mut Self::Columns, additional: usize) {
+        V::reserve(&mut columns.0, additional);
+        Rest::reserve(&mut columns.1, additional);
+    }
+
+    #[inline(always)]
+    fn push(columns: &mut Self::Columns, value: Self::Scalar) {
+        let vlist_pat![v, ...rest] = value;
+        V::push(&mut columns.0, v);
+        Rest::push(&mut columns.1, rest);
+    }
+
+    #[inline(always)]
+    fn pop(columns: &mut Self::Columns) -> Option<Self::Scalar> {
+        match (V::pop(&mut columns.0), Rest::pop(&mut columns.1)) {
+            (Some(v), Some(rest)) => Some(vlist![v, ...rest]),
+            _ => None,
+        }
+    }
+
+    #[inline(always)]
+    fn truncate(columns: &mut Self::Columns, len: usize) {
+        V::truncate(&mut columns.0, len);
+        Rest::truncate(&mut columns.1, len);
+    }
+
+    #[inline(always)]
+    fn get(columns: &Self::Columns, idx: usize) -> Self::Scalar {
+        vlist![V::get(&columns.0, idx), ...Rest::get(&columns.1, idx)]
+    }
+
+    #[inline(always)]
+    fn set(columns: &mut Self::Columns, idx: usize, value: Self::Scalar) {
+        let vlist_pat![v, ...rest] = value;
+        V::set(&mut columns.0, idx, v);
+        Rest::set(&mut columns.1, idx, rest);
+    }
+
+    #[inline(always)]
+    fn as_slices<'a>(columns: &'a Self::Columns) -> <Self as PackedGats<'a>>::ScalarSlice {
+        vlist![V::as_slices(&columns.0), ...Rest::as_slices(&columns.1)]
+    }
+
+    #[inline(always)]
+    fn as_slices_mut<'a>(columns: &'a mut Self::Columns) -> <Self as PackedGats<'a>>::ScalarSliceMut {
+        let vlist_pat![v, ...rest] = columns;
+        vlist![V::as_slices_mut(v), ...Rest::as_slices_mut(rest)]
+    }
+
+    #[inline(always)]
+    fn load_chunk(columns: &Self::Columns, chunk: usize) -> Self {
+        vlist![V::load_chunk(&columns.0, chunk), ...Rest::load_chunk(&columns.1, chunk)]
+    }
+
+    #[inline(always)]
+    fn store_chunk(self, columns: &mut Self::Columns, chunk: usize) {
+        let vlist_pat![v, ...rest] = self;
+        v.store_chunk(&mut columns.0, chunk);
+        rest.store_chunk(&mut columns.1, chunk);
+    }
+}
+
+impl<T> PackedColumns for T
+where
+    T: CustomPacked,
+    T::BaseVector: PackedColumns,
+{
+    type Columns = <T::BaseVector as PackedColumns>::Columns;
+
+    #[inline(always)]
+    fn len(columns: &Self::Columns) -> usize {
+        T::BaseVector::len(columns)
+    }
+
+    #[inline(always)]
+    fn reserve(columns: &mut Self::Columns, additional: usize) {
+        T::BaseVector::reserve(columns, additional)
+    }
+
+    #[inline(always)]
+    fn push(columns: &mut Self::Columns, value: Self::Scalar) {
+        T::BaseVector::push(columns, T::scalar_into_base(value))
+    }
+
+    #[inline(always)]
+    fn pop(columns: &mut Self::Columns) -> Option<Self::Scalar> {
+        T::BaseVector::pop(columns).map(T::scalar_from_base)
+    }
+
+    #[inline(always)]
+    fn truncate(columns: &mut Self::Columns, len: usize) {
+        T::BaseVector::truncate(columns, len)
+    }
+
+    #[inline(always)]
+    fn get(columns: &Self::Columns, idx: usize) -> Self::Scalar {
+        T::scalar_from_base(T::BaseVector::get(columns, idx))
+    }
+
+    #[inline(always)]
+    fn set(columns: &mut Self::Columns, idx: usize, value: Self::Scalar) {
+        T::BaseVector::set(columns, idx, T::scalar_into_base(value))
+    }
+
+    #[inline(always)]
+    fn as_slices<'a>(columns: &'a Self::Columns) -> <Self as PackedGats<'a>>::ScalarSlice {
+        T::BaseVector::as_slices(columns)
+    }
+
+    #[inline(always)]
+    fn as_slices_mut<'a>(columns: &'a mut Self::Columns) -> <Self as PackedGats<'a>>::ScalarSliceMut {
+        T::BaseVector::as_slices_mut(columns)
+    }
+
+    #[inline(always)]
+    fn load_chunk(columns: &Self::Columns, chunk: usize) -> Self {
+        T::vector_from_base(T::BaseVector::load_chunk(columns, chunk))
+    }
+
+    #[inline(always)]
+    fn store_chunk(self, columns: &mut Self::Columns, chunk: usize) {
+        T::vector_into_base(self).store_chunk(columns, chunk)
+    }
+}
+
+//--------------------------------------------------------------------------------
+
+/// A structure-of-arrays `Vec` of `T`, which is typically a VList or a custom scalar type
+/// (like `Complex<f64>`).
+///
+/// Each primitive field of `T` lives in its own `AlignedVec`, so that the columns can be
+/// borrowed as `T::Vector`'s `ScalarSlice` (a VList of slices), and loaded from with
+/// aligned loads.
+pub struct SoaVec<T>
+where
+    T: Packable,
+    T::Vector: PackedColumns,
+{
+    columns: <T::Vector as PackedColumns>::Columns,
+}
+
+impl<T> SoaVec<T>
+where
+    T: Packable,
+    T::Vector: PackedColumns,
+{
+    pub fn new() -> Self {
+        SoaVec { columns: Default::default() }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut out = Self::new();
+        out.reserve(capacity);
+        out
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize { T::Vector::len(&self.columns) }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    pub fn reserve(&mut self, additional: usize) {
+        T::Vector::reserve(&mut self.columns, additional)
+    }
+
+    pub fn push(&mut self, value: T) {
+        T::Vector::push(&mut self.columns, value)
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        T::Vector::pop(&mut self.columns)
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        T::Vector::truncate(&mut self.columns, len)
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0)
+    }
+
+    /// Read the element at `idx`.  Panics if `idx` is out of bounds.
+    #[inline]
+    pub fn get(&self, idx: usize) -> T {
+        assert!(idx < self.len(), "SoaVec::get: index {} out of bounds (len {})", idx, self.len());
+        T::Vector::get(&self.columns, idx)
+    }
+
+    /// Overwrite the element at `idx`.  Panics if `idx` is out of bounds.
+    #[inline]
+    pub fn set(&mut self, idx: usize, value: T) {
+        assert!(idx < self.len(), "SoaVec::set: index {} out of bounds (len {})", idx, self.len());
+        T::Vector::set(&mut self.columns, idx, value)
+    }
+
+    /// Borrow the columns, e.g. as a VList of slices.
+    ///
+    /// For a custom type, this is a VList of the columns of its `BaseVector`.
+    #[inline]
+    pub fn columns<'a>(&'a self) -> <T::Vector as PackedGats<'a>>::ScalarSlice {
+        T::Vector::as_slices(&self.columns)
+    }
+
+    /// Borrow the columns mutably, e.g. as a VList of slices.
+    #[inline]
+    pub fn columns_mut<'a>(&'a mut self) -> <T::Vector as PackedGats<'a>>::ScalarSliceMut {
+        T::Vector::as_slices_mut(&mut self.columns)
+    }
+
+    /// Iterate over the elements in chunks of `T::Vector`.
+    ///
+    /// The final chunk is padded with zeros (or rather, whatever `T::Vector::default()`
+    /// would contain), so there is never a remainder.
+    #[inline]
+    pub fn simd_iter<'a>(&'a self) -> SoaIter<'a, T> {
+        SoaIter { soa: self, chunk: 0 }
+    }
+
+    /// Replace every chunk of `T::Vector` with `func(chunk)`.
+    ///
+    /// As with `simd_iter`, `func` will see the zero padding in the final chunk.
+    /// Whatever it makes of those lanes is discarded.
+    pub fn simd_map_in_place<F>(&mut self, mut func: F)
+    where F: FnMut(T::Vector) -> T::Vector,
+    {
+        for chunk in 0..num_chunks::<T::Vector>(self.len()) {
+            let vector = T::Vector::load_chunk(&self.columns, chunk);
+            func(vector).store_chunk(&mut self.columns, chunk);
+        }
+    }
+}
+
+#[inline(always)]
+fn num_chunks<V: Packed>(len: usize) -> usize {
+    (len + V::WIDTH - 1) / V::WIDTH
+}
+
+impl<T> Default for SoaVec<T>
+where
+    T: Packable,
+    T::Vector: PackedColumns,
+{
+    fn default() -> Self { Self::new() }
+}
+
+impl<T> Clone for SoaVec<T>
+where
+    T: Packable,
+    T::Vector: PackedColumns,
+{
+    fn clone(&self) -> Self {
+        SoaVec { columns: self.columns.clone() }
+    }
+}
+
+impl<T> Extend<T> for SoaVec<T>
+where
+    T: Packable,
+    T::Vector: PackedColumns,
+{
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for SoaVec<T>
+where
+    T: Packable,
+    T::Vector: PackedColumns,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut out = Self::new();
+        out.extend(iter);
+        out
+    }
+}
+
+/// Created by `SoaVec::simd_iter`.
+pub struct SoaIter<'a, T>
+where
+    T: Packable + 'a,
+    T::Vector: PackedColumns,
+{
+    soa: &'a SoaVec<T>,
+    chunk: usize,
+}
+
+impl<'a, T> Iterator for SoaIter<'a, T>
+where
+    T: Packable + 'a,
+    T::Vector: PackedColumns,
+{
+    type Item = T::Vector;
+
+    #[inline(always)]
+    fn next(&mut self) -> Option<T::Vector> {
+        if self.chunk == num_chunks::<T::Vector>(self.soa.len()) {
+            return None;
+        }
+        let vector = T::Vector::load_chunk(&self.soa.columns, self.chunk);
+        self.chunk += 1;
+        Some(vector)
+    }
+
+    #[inline(always)]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = num_chunks::<T::Vector>(self.soa.len()) - self.chunk;
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for SoaIter<'a, T>
+where
+    T: Packable + 'a,
+    T::Vector: PackedColumns,
+{}
+
+impl<'a, T> SimdIterator for SoaIter<'a, T>
+where
+    T: Packable + 'a,
+    T::Vector: PackedColumns,
+{
+    /// The final chunk is padded, so there's never anything left over.
+    type Remainder = ();
+
+    #[inline(always)]
+    fn remainder(&self) {}
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::faster::{f32s, f64s};
+
+    #[derive(Debug, Copy, Clone, PartialEq, CustomPacked)]
+    #[custom_packed(root = "")]
+    struct Point<V> { x: V, y: V }
+
+    impl Packable for Point<f64> {
+        type Vector = Point<f64s>;
+    }
+
+    #[test]
+    fn custom() {
+        let n = 2 * f64s::WIDTH + 1;
+        let mut points: SoaVec<Point<f64>> = (0..n).map(|i| Point { x: i as f64, y: 1.0 }).collect();
+        assert_eq!(points.len(), n);
+        assert_eq!(points.get(2), Point { x: 2.0, y: 1.0 });
+
+        points.set(0, Point { x: 0.0, y: 5.0 });
+        {
+            let vlist_pat![xs, ys] = points.columns();
+            assert_eq!(xs.len(), n);
+            assert_eq!(ys[0], 5.0);
+        }
+
+        points.simd_map_in_place(|p| Point { x: p.x + p.y, y: p.y });
+        assert_eq!(points.get(n - 1), Point { x: n as f64, y: 1.0 });
+
+        let sums = points.simd_iter().simd_reduce(f64s::splat(0.0), |acc, p| acc + p.y);
+        assert_eq!(sums.scalar_reduce(0.0, |a, b| a + b), (n + 4) as f64);
+
+        assert_eq!(points.pop(), Some(Point { x: n as f64, y: 1.0 }));
+        assert_eq!(points.len(), n - 1);
+    }
+
+    #[test]
+    fn vlist() {
+        let mut soa = SoaVec::<VList![f32, f64]>::new();
+        soa.push(vlist![1.0, 2.0]);
+        soa.push(vlist![3.0, 4.0]);
+        assert_eq!(soa.get(1), vlist![3.0, 4.0]);
+
+        let vlist_pat![fs, ds] = soa.columns_mut();
+        fs[0] = 10.0;
+        ds[1] = 20.0;
+        assert_eq!(soa.simd_iter().count(), 1);
+        assert_eq!(soa.simd_iter().next().unwrap().0.extract(0), 10.0);
+        assert_eq!(soa.get(1), vlist![3.0, 20.0]);
+        assert!(<VList![f32, f64] as Packable>::Vector::WIDTH <= f32s::WIDTH);
+    }
+}
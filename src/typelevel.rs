@@ -0,0 +1,68 @@
+use ::std::ops::{Shl, Shr};
+use ::typenum::{B1, Unsigned};
+
+// Type-level natural numbers are provided by typenum.  This module re-exports
+// the parts of it that we need (so that users don't need to depend on it directly),
+// and adds a couple of operations that come up when splitting and joining vectors.
+
+pub use ::typenum::consts::{U1, U2, U4, U8, U16, U32, U64};
+pub use ::typenum::{Cmp, Min, Max, IsEqual, IsLess, IsLessOrEqual, IsGreater, IsGreaterOrEqual};
+pub use ::typenum::{Compare, Minimum, Maximum, Sum};
+
+/// A type-level natural number.
+///
+/// This is `typenum::Unsigned` under a more descriptive name.
+pub trait TypeLevelUsize: Unsigned {
+    const VALUE: usize;
+}
+
+impl<N: Unsigned> TypeLevelUsize for N {
+    const VALUE: usize = N::USIZE;
+}
+
+/// Type-level `N * 2`; the width of two vectors joined together.
+pub trait Double: TypeLevelUsize {
+    type Output: TypeLevelUsize;
+}
+
+impl<N> Double for N
+where
+    N: TypeLevelUsize + Shl<B1>,
+    <N as Shl<B1>>::Output: TypeLevelUsize,
+{
+    type Output = <N as Shl<B1>>::Output;
+}
+
+/// Type-level `N / 2` (rounding down); the width of each half of a split vector.
+pub trait Half: TypeLevelUsize {
+    type Output: TypeLevelUsize;
+}
+
+impl<N> Half for N
+where
+    N: TypeLevelUsize + Shr<B1>,
+    <N as Shr<B1>>::Output: TypeLevelUsize,
+{
+    type Output = <N as Shr<B1>>::Output;
+}
+
+pub type Doubled<N> = <N as Double>::Output;
+pub type Halved<N> = <N as Half>::Output;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::typenum::Bit;
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(<Doubled<U16>>::VALUE, 32);
+        assert_eq!(<Halved<U16>>::VALUE, 8);
+        assert_eq!(<Halved<U1>>::VALUE, 0);
+        assert_eq!(<Minimum<U4, U64>>::VALUE, 4);
+        assert_eq!(<Maximum<U4, U64>>::VALUE, 64);
+        assert_eq!(<Sum<U4, U2>>::VALUE, 6);
+        assert!(<U4 as IsLess<U8>>::Output::to_bool());
+        assert!(!<U8 as IsLess<U4>>::Output::to_bool());
+    }
+}
@@ -1,6 +1,13 @@
 use ::std::ops;
 use ::std::cmp;
 use ::std::iter::FusedIterator;
+use ::faster::vecs::{f32x4, f32x8, f32x16, f64x2, f64x4, f64x8};
+use ::faster::vecs::{i8x16, i8x32, i8x64, i16x8, i16x16, i16x32};
+use ::faster::vecs::{i32x4, i32x8, i32x16, i64x2, i64x4, i64x8};
+use ::faster::vecs::{u8x16, u8x32, u8x64, u16x8, u16x16, u16x32};
+use ::faster::vecs::{u32x4, u32x8, u32x16, u64x2, u64x4, u64x8};
+use ::std::simd::{f32x2, i8x2, i8x4, i8x8, i16x2, i16x4, i32x2};
+use ::std::simd::{u8x2, u8x4, u8x8, u16x2, u16x4, u32x2};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct Cons<V, Vs>(pub V, pub Vs);
@@ -60,6 +67,114 @@ macro_rules! impl_std_unop {
     }
 }
 
+// `&a + &b` walks the spine of the lists by reference, but clones each head whole and
+// applies the by-value operator to it, since the SIMD vectors don't implement the operators
+// on references.  (cloning those is free; a head that is itself a VList is cloned entirely)
+macro_rules! impl_std_ref_binop {
+    (ops::$Add:ident::$add:ident) => {
+        impl<'a, 'b> ops::$Add<&'b Nil> for &'a Nil {
+            type Output = Nil;
+
+            fn $add(self, _: &'b Nil) -> Nil { Nil }
+        }
+
+        impl<'a, 'b, H1, H2, HOut, T1, T2, TOut> ops::$Add<&'b Cons<H2, T2>> for &'a Cons<H1, T1>
+        where
+            H1: Clone + ops::$Add<H2, Output=HOut>,
+            H2: Clone,
+            &'a T1: ops::$Add<&'b T2, Output=TOut>,
+        {
+            type Output = Cons<HOut, TOut>;
+
+            fn $add(self, other: &'b Cons<H2, T2>) -> Self::Output {
+                Cons(
+                    ops::$Add::$add(self.0.clone(), other.0.clone()),
+                    ops::$Add::$add(&self.1, &other.1),
+                )
+            }
+        }
+    }
+}
+
+macro_rules! impl_std_ref_unop {
+    (ops::$Neg:ident::$neg:ident) => {
+        impl<'a> ops::$Neg for &'a Nil {
+            type Output = Nil;
+
+            fn $neg(self) -> Nil { Nil }
+        }
+
+        impl<'a, H, HOut, T, TOut> ops::$Neg for &'a Cons<H, T>
+        where
+            H: Clone + ops::$Neg<Output=HOut>,
+            &'a T: ops::$Neg<Output=TOut>,
+        {
+            type Output = Cons<HOut, TOut>;
+
+            fn $neg(self) -> Self::Output {
+                Cons(
+                    ops::$Neg::$neg(self.0.clone()),
+                    ops::$Neg::$neg(&self.1),
+                )
+            }
+        }
+    }
+}
+
+macro_rules! impl_std_assign_op {
+    (ops::$AddAssign:ident::$add_assign:ident) => {
+        impl ops::$AddAssign<Nil> for Nil {
+            fn $add_assign(&mut self, Nil: Nil) {}
+        }
+
+        impl<H1, H2, T1, T2> ops::$AddAssign<Cons<H2, T2>> for Cons<H1, T1>
+        where
+            H1: ops::$AddAssign<H2>,
+            T1: ops::$AddAssign<T2>,
+        {
+            fn $add_assign(&mut self, other: Cons<H2, T2>) {
+                ops::$AddAssign::$add_assign(&mut self.0, other.0);
+                ops::$AddAssign::$add_assign(&mut self.1, other.1);
+            }
+        }
+    }
+}
+
+// `vlist * 2.0` or `vlist * f64s::splat(2.0)` applies the operator to every element.
+//
+// (a blanket impl over the right-hand side would overlap with the VList impls above,
+//  so this is done for each scalar and vector type)
+macro_rules! impl_std_broadcast_binop {
+    ([$($S:ty),*] ops::$Add:ident::$add:ident) => {$(
+        impl ops::$Add<$S> for Nil {
+            type Output = Nil;
+
+            fn $add(self, _: $S) -> Nil { Nil }
+        }
+
+        impl<H, HOut, T, TOut> ops::$Add<$S> for Cons<H, T>
+        where
+            H: ops::$Add<$S, Output=HOut>,
+            T: ops::$Add<$S, Output=TOut>,
+        {
+            type Output = Cons<HOut, TOut>;
+
+            fn $add(self, other: $S) -> Self::Output {
+                Cons(
+                    ops::$Add::$add(self.0, other),
+                    ops::$Add::$add(self.1, other),
+                )
+            }
+        }
+    )*}
+}
+
+macro_rules! impl_std_broadcast_binops {
+    ($types:tt; $(ops::$Add:ident::$add:ident),*) => {
+        $(impl_std_broadcast_binop!{$types ops::$Add::$add})*
+    }
+}
+
 impl_std_binop!{ops::Add::add}
 impl_std_binop!{ops::Sub::sub}
 impl_std_binop!{ops::Mul::mul}
@@ -73,6 +188,45 @@ impl_std_binop!{ops::Shr::shr}
 impl_std_unop!{ops::Neg::neg}
 impl_std_unop!{ops::Not::not}
 
+impl_std_broadcast_binops!{
+    [
+        f32, f64, i8, i16, i32, i64, u8, u16, u32, u64,
+        f32x2, f32x4, f32x8, f32x16, f64x2, f64x4, f64x8,
+        i8x2, i8x4, i8x8, i8x16, i8x32, i8x64,
+        i16x2, i16x4, i16x8, i16x16, i16x32,
+        i32x2, i32x4, i32x8, i32x16, i64x2, i64x4, i64x8,
+        u8x2, u8x4, u8x8, u8x16, u8x32, u8x64,
+        u16x2, u16x4, u16x8, u16x16, u16x32,
+        u32x2, u32x4, u32x8, u32x16, u64x2, u64x4, u64x8
+    ];
+    ops::Add::add, ops::Sub::sub, ops::Mul::mul, ops::Div::div, ops::Rem::rem,
+    ops::BitAnd::bitand, ops::BitOr::bitor, ops::BitXor::bitxor, ops::Shl::shl, ops::Shr::shr
+}
+
+impl_std_ref_binop!{ops::Add::add}
+impl_std_ref_binop!{ops::Sub::sub}
+impl_std_ref_binop!{ops::Mul::mul}
+impl_std_ref_binop!{ops::Div::div}
+impl_std_ref_binop!{ops::Rem::rem}
+impl_std_ref_binop!{ops::BitAnd::bitand}
+impl_std_ref_binop!{ops::BitOr::bitor}
+impl_std_ref_binop!{ops::BitXor::bitxor}
+impl_std_ref_binop!{ops::Shl::shl}
+impl_std_ref_binop!{ops::Shr::shr}
+impl_std_ref_unop!{ops::Neg::neg}
+impl_std_ref_unop!{ops::Not::not}
+
+impl_std_assign_op!{ops::AddAssign::add_assign}
+impl_std_assign_op!{ops::SubAssign::sub_assign}
+impl_std_assign_op!{ops::MulAssign::mul_assign}
+impl_std_assign_op!{ops::DivAssign::div_assign}
+impl_std_assign_op!{ops::RemAssign::rem_assign}
+impl_std_assign_op!{ops::BitAndAssign::bitand_assign}
+impl_std_assign_op!{ops::BitOrAssign::bitor_assign}
+impl_std_assign_op!{ops::BitXorAssign::bitxor_assign}
+impl_std_assign_op!{ops::ShlAssign::shl_assign}
+impl_std_assign_op!{ops::ShrAssign::shr_assign}
+
 //-------------------------------------------------------------------------
 // iterator boilerplate
 
@@ -138,29 +292,98 @@ where
 }
 
 //-------------------------------------------------------------------------
+// tuple conversions
 
-// TODO: equivalent to Packable, for automatically picking the largest type.
-//       When zipping two types of mismatched "largest SIMD widths", it should
-//       chose the minimum width; this sounds like some pretty difficult typelevel programming.
-//
-//pub trait Packable {
-//    type Vector: IsVector<Scalar=Self>;
-//    type Size: IsSize;
-//    const SIZE: Self::Size;
-//}
-//
-//impl Packable for Nil {
-//    type Vector = Nil;
-//    type Size = Nil;
-//    const SIZE: Self::Size = Nil;
-//}
-//
-//impl<H, T> Packable for Cons<H, T>
-//where
-//    H: Packable,
-//    T: Packable,
-//{
-//    type Vector = Cons<H::Vector, T::Vector>;
-//    type Size = Cons<H::Size, T::Size>;
-//    const SIZE: Self::Size = Nil;
-//}
+/// Converts a VList into the tuple with the same elements.
+///
+/// (the reverse direction is just `From`)
+pub trait IntoTuple: Sized {
+    type Tuple: Into<Self>;
+
+    fn into_tuple(self) -> Self::Tuple;
+}
+
+macro_rules! impl_tuple_conversions {
+    () => {};
+    ($A:ident $a:ident, $($B:ident $b:ident,)*) => {
+        impl<$A, $($B),*> From<($A, $($B,)*)> for VList![$A, $($B),*] {
+            #[inline(always)]
+            fn from(($a, $($b,)*): ($A, $($B,)*)) -> Self {
+                vlist![$a, $($b),*]
+            }
+        }
+
+        impl<$A, $($B),*> From<VList![$A, $($B),*]> for ($A, $($B,)*) {
+            #[inline(always)]
+            fn from(vlist_pat![$a, $($b),*]: VList![$A, $($B),*]) -> Self {
+                ($a, $($b,)*)
+            }
+        }
+
+        impl<$A, $($B),*> IntoTuple for VList![$A, $($B),*] {
+            type Tuple = ($A, $($B,)*);
+
+            #[inline(always)]
+            fn into_tuple(self) -> Self::Tuple { self.into() }
+        }
+
+        impl_tuple_conversions!{ $($B $b,)* }
+    };
+}
+
+impl_tuple_conversions!{
+    A a, B b, C c, D d, E e, F f, G g, H h, I i, J j, K k, L l,
+}
+
+impl From<()> for Nil {
+    #[inline(always)]
+    fn from((): ()) -> Nil { Nil }
+}
+
+impl From<Nil> for () {
+    #[inline(always)]
+    fn from(Nil: Nil) {}
+}
+
+impl IntoTuple for Nil {
+    type Tuple = ();
+
+    #[inline(always)]
+    fn into_tuple(self) {}
+}
+
+#[cfg(test)]
+mod test {
+    use ::faster::{f32s, f64s, i32s, u8s};
+
+    #[test]
+    fn assign_ops() {
+        let mut acc = vlist![f32s::splat(1.0), i32s::splat(2)];
+        acc += vlist![f32s::splat(0.5), i32s::splat(3)];
+        acc *= vlist![f32s::splat(2.0), i32s::splat(2)];
+        assert_eq!(acc, vlist![f32s::splat(3.0), i32s::splat(10)]);
+
+        // heterogeneous right-hand sides, as far as the elements allow
+        let mut xs = vlist![vlist![u8s::splat(1)], 7u32];
+        xs <<= vlist![vlist![u8s::splat(2)], 1u32];
+        xs ^= vlist![vlist![u8s::splat(1)], 1u32];
+        assert_eq!(xs, vlist![vlist![u8s::splat(5)], 15]);
+    }
+
+    #[test]
+    fn broadcast_ops() {
+        let v = vlist![f64s::splat(1.0), vlist![f64s::splat(2.0)]];
+        assert_eq!(v * 2.0, vlist![f64s::splat(2.0), vlist![f64s::splat(4.0)]]);
+        assert_eq!(v - f64s::splat(1.0), vlist![f64s::splat(0.0), vlist![f64s::splat(1.0)]]);
+        assert_eq!(vlist![i32s::splat(6), 7] & 3, vlist![i32s::splat(2), 3]);
+    }
+
+    #[test]
+    fn ref_ops() {
+        let a = vlist![f32s::splat(1.0), vlist![i32s::splat(2), i32s::splat(3)]];
+        let b = vlist![f32s::splat(2.0), vlist![i32s::splat(5), i32s::splat(7)]];
+        assert_eq!(&a + &b, a + b);
+        assert_eq!(&b - &a, vlist![f32s::splat(1.0), vlist![i32s::splat(3), i32s::splat(4)]]);
+        assert_eq!(-&a, -a);
+    }
+}
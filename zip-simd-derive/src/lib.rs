@@ -0,0 +1,470 @@
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use]
+extern crate syn;
+#[macro_use]
+extern crate quote;
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{Tokens, ToTokens};
+use syn::{Data, DeriveInput, Fields, GenericArgument, GenericParam, Ident, Lit, Meta, NestedMeta};
+use syn::{Path, PathArguments, ReturnType, Type};
+
+/// Derives `CustomPacked` for a struct whose fields all implement `Packed`.
+///
+/// The `BaseVector` is a VList of the field types, in declaration order.
+///
+/// By default, the scalar type is the same struct with every type parameter `V`
+/// replaced by `V::Scalar` (so `Complex<f64s>` has the scalar `Complex<f64>`).
+/// This only makes sense if every field's type involves a type parameter.
+///
+/// Every type parameter gets a `Packed` bound, and the fields must all have the same
+/// `Width`.  A field type that mentions one parameter `V` is taken to have the width of `V`,
+/// and parameters that are field types by themselves (like `W` in
+/// `struct Labeled<V, W> { z: Complex<V>, w: W }`) get `Width` bounds to match the rest.
+/// If that can't relate every field (say, `{ z: Complex<V>, a: [U; 2] }`), the derive fails
+/// and asks for the bounds to be written on the struct instead.
+///
+/// Attributes:
+///
+/// * `#[custom_packed(scalar = "Point<f64>")]` uses a different struct as the scalar type.
+///   It must have the same fields as the vector struct.
+/// * `#[custom_packed(root = "::zip_simd")]` is the path to the crate that defines
+///   `CustomPacked`. (this is the default; inside that crate, use `root = ""`)
+/// * `#[custom_packed(ops = "Add, Sub, Neg")]` implements those `std::ops` traits
+///   element-wise, through the `BaseVector` (like `custom_packed_ops!`).  Leave out any
+///   operator that needs different semantics, and implement it by hand.
+#[proc_macro_derive(CustomPacked, attributes(custom_packed))]
+pub fn derive_custom_packed(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse(input).expect("derive(CustomPacked): could not parse input");
+    expand_custom_packed(input).into()
+}
+
+struct Options {
+    root: Tokens,
+    scalar: Option<Path>,
+    ops: Vec<Ident>,
+}
+
+fn parse_options(attrs: &[syn::Attribute]) -> Options {
+    let mut options = Options { root: quote!{ ::zip_simd }, scalar: None, ops: vec![] };
+
+    let metas = attrs.iter().filter_map(|attr| attr.interpret_meta());
+    for meta in metas.filter(|meta| meta.name() == "custom_packed") {
+        let list = match meta {
+            Meta::List(list) => list,
+            _ => panic!("derive(CustomPacked): expected #[custom_packed(...)]"),
+        };
+        for nested in list.nested {
+            let (name, value) = match nested {
+                NestedMeta::Meta(Meta::NameValue(syn::MetaNameValue { ident, lit: Lit::Str(s), .. })) => {
+                    (ident, s.value())
+                },
+                _ => panic!("derive(CustomPacked): expected `key = \"value\"` in #[custom_packed(...)]"),
+            };
+
+            let parse_path = |s: &str| -> Path {
+                syn::parse_str(s).unwrap_or_else(|_| panic!("derive(CustomPacked): not a path: {:?}", s))
+            };
+
+            if name == "root" {
+                options.root = match value.as_str() {
+                    "" => quote!{},
+                    s => parse_path(s).into_tokens(),
+                };
+            } else if name == "scalar" {
+                options.scalar = Some(parse_path(&value));
+            } else if name == "ops" {
+                for op in value.split(',').map(str::trim).filter(|op| !op.is_empty()) {
+                    if op_method(op).is_none() {
+                        panic!("derive(CustomPacked): unknown operator {:?}", op);
+                    }
+                    options.ops.push(Ident::new(op, Span::call_site()));
+                }
+            } else {
+                panic!("derive(CustomPacked): unknown option {:?}", name.as_ref());
+            }
+        }
+    }
+    options
+}
+
+/// The method of each supported `std::ops` trait, and whether it is binary.
+fn op_method(op: &str) -> Option<(&'static str, bool)> {
+    Some(match op {
+        "Add" => ("add", true),
+        "Sub" => ("sub", true),
+        "Mul" => ("mul", true),
+        "Div" => ("div", true),
+        "Rem" => ("rem", true),
+        "BitAnd" => ("bitand", true),
+        "BitOr" => ("bitor", true),
+        "BitXor" => ("bitxor", true),
+        "Shl" => ("shl", true),
+        "Shr" => ("shr", true),
+        "Neg" => ("neg", false),
+        "Not" => ("not", false),
+        _ => return None,
+    })
+}
+
+/// The parts of a struct definition needed to take it apart and put it back together.
+struct Shape {
+    named: bool,
+    bindings: Vec<Ident>,
+    types: Vec<Type>,
+}
+
+impl Shape {
+    fn new(fields: Fields) -> Shape {
+        let named = match fields {
+            Fields::Named(_) => true,
+            Fields::Unnamed(_) => false,
+            Fields::Unit => panic!("derive(CustomPacked): the struct must have at least one field"),
+        };
+
+        let mut bindings = vec![];
+        let mut types = vec![];
+        for (i, field) in fields.iter().enumerate() {
+            let binding = match field.ident {
+                Some(ident) => ident,
+                None => Ident::new(&format!("__field{}", i), Span::call_site()),
+            };
+            bindings.push(binding);
+            types.push(field.ty.clone());
+        }
+        if bindings.is_empty() {
+            panic!("derive(CustomPacked): the struct must have at least one field");
+        }
+        Shape { named, bindings, types }
+    }
+
+    /// `Path { a, b }` or `Path(__field0, __field1)`.
+    fn expr(&self, path: &Tokens) -> Tokens {
+        let bindings = &self.bindings;
+        match self.named {
+            true => quote!{ #path { #(#bindings),* } },
+            false => quote!{ #path ( #(#bindings),* ) },
+        }
+    }
+
+    /// Like `expr`, but as a pattern that binds each field by `ref` or `ref mut`.
+    fn ref_pat(&self, path: &Tokens, mode: Tokens) -> Tokens {
+        let bindings = &self.bindings;
+        let modes = ::std::iter::repeat(&mode);
+        match self.named {
+            true => quote!{ #path { #(#modes #bindings),* } },
+            false => quote!{ #path ( #(#modes #bindings),* ) },
+        }
+    }
+}
+
+/// `Cons(a, Cons(b, Nil))`, which works as an expression, pattern or type.
+fn vlist<I>(root: &Tokens, items: I) -> Tokens
+where
+    I: IntoIterator<Item = Tokens>,
+    I::IntoIter: DoubleEndedIterator,
+{
+    items.into_iter().rev().fold(quote!{ #root::Nil }, |rest, item| {
+        quote!{ #root::Cons(#item, #rest) }
+    })
+}
+
+fn vlist_type<I>(root: &Tokens, items: I) -> Tokens
+where
+    I: IntoIterator<Item = Tokens>,
+    I::IntoIter: DoubleEndedIterator,
+{
+    items.into_iter().rev().fold(quote!{ #root::Nil }, |rest, item| {
+        quote!{ #root::Cons<#item, #rest> }
+    })
+}
+
+fn expand_custom_packed(input: DeriveInput) -> Tokens {
+    let Options { root, scalar, ops } = parse_options(&input.attrs);
+    let name = input.ident;
+    let shape = match input.data {
+        Data::Struct(data) => Shape::new(data.fields),
+        _ => panic!("derive(CustomPacked): only structs are supported"),
+    };
+
+    let packed = quote!{ #root::Packed };
+    let gats = quote!{ #root::packed::PackedGats };
+    let mut generics = input.generics;
+
+    // The scalar type, and a path to it for use in patterns.
+    let (scalar_type, scalar_path) = match scalar {
+        Some(path) => {
+            let mut bare = path.clone();
+            if let Some(segment) = bare.segments.iter_mut().last() {
+                segment.arguments = syn::PathArguments::None;
+            }
+            (path.into_tokens(), bare.into_tokens())
+        },
+        None => {
+            if generics.type_params().next().is_none() {
+                panic!("derive(CustomPacked): a struct without type parameters needs #[custom_packed(scalar = \"...\")]");
+            }
+            let args = generics.params.iter().map(|param| match *param {
+                GenericParam::Type(ref param) => {
+                    let ident = &param.ident;
+                    quote!{ <#ident as #packed>::Scalar }
+                },
+                GenericParam::Lifetime(ref def) => def.lifetime.into_tokens(),
+                GenericParam::Const(ref param) => param.ident.into_tokens(),
+            }).collect::<Vec<_>>();
+            (quote!{ #name<#(#args),*> }, quote!{ #name })
+        },
+    };
+
+    // Every type parameter must be Packed so that `V::Scalar` makes sense,
+    // and the field types must all share a width.
+    //
+    // A field type that mentions a single parameter `V` is assumed to have the width of `V`
+    // (true of `[V; 2]`, `Complex<V>`, ...), and concrete field types are left for rustc to
+    // compare, so the fields fall into one group per parameter, plus one for concrete types.
+    // (types that mention several parameters just need those groups to agree)
+    //
+    // Width bounds can only go on parameters that are field types by themselves: a bound on
+    // any other type would shadow its impls (hiding e.g. its `Scalar`), and a parameter with
+    // two `Width` bounds is ambiguous.  So every such parameter gets one bound, equating it
+    // with a single root group, and any other group can't be related and is an error.
+    let type_params = generics.type_params().map(|param| param.ident).collect::<Vec<_>>();
+    let has_own_bounds = generics.type_params().any(|param| !param.bounds.is_empty())
+        || generics.where_clause.iter().any(|clause| !clause.predicates.is_empty());
+    {
+        let bare = shape.types.iter()
+            .filter_map(|ty| as_param(ty, &type_params))
+            .fold(vec![], |mut bare, param| { if !bare.contains(&param) { bare.push(param) } bare });
+
+        // The groups with no bare parameter to bound, each with the first field type in it.
+        // (`None` is the group of concrete types)
+        let mut unbound: Vec<(Option<Ident>, &Type)> = vec![];
+        for ty in &shape.types {
+            let mentioned = mentioned_params(ty, &type_params);
+            let groups = match mentioned.len() {
+                0 => vec![None],
+                _ => mentioned.into_iter().map(Some).collect(),
+            };
+            for group in groups {
+                let bound = bare.iter().any(|&param| Some(param) == group);
+                if !bound && !unbound.iter().any(|&(other, _)| other == group) {
+                    unbound.push((group, ty));
+                }
+            }
+        }
+        let unrelated = |a: &Type, b: &Type| -> ! {
+            panic!(
+                "derive(CustomPacked): can't tell that the field types `{}` and `{}` have \
+                 the same width; add the `Width` bounds to the struct's where clause",
+                a.into_tokens(), b.into_tokens(),
+            )
+        };
+
+        let where_clause = generics.make_where_clause();
+        for ident in &type_params {
+            where_clause.predicates.push(parse_quote!{ #ident: #packed });
+        }
+
+        let root: Option<Type> = match unbound.len() {
+            0 => bare.first().map(|&param| parse_quote!{ #param }),
+            1 => {
+                // The bounds can't point at a type that mentions a bare parameter too.
+                let (group, first) = unbound[0];
+                let alone = shape.types.iter().find(|ty| {
+                    mentioned_params(ty, &type_params) == group.into_iter().collect::<Vec<_>>()
+                });
+                match alone {
+                    Some(ty) => Some(ty.clone()),
+                    None if has_own_bounds => None,
+                    None => {
+                        let mentioned = mentioned_params(first, &type_params);
+                        let other = shape.types.iter().find(|ty| {
+                            as_param(ty, &type_params).into_iter().any(|param| mentioned.contains(&param))
+                        });
+                        unrelated(first, other.unwrap())
+                    },
+                }
+            },
+            _ if has_own_bounds => None,
+            _ => unrelated(unbound[0].1, unbound[1].1),
+        };
+        if let Some(root) = root {
+            for param in &bare {
+                if as_param(&root, &type_params) != Some(*param) {
+                    where_clause.predicates.push(parse_quote!{
+                        #param: #packed<Width = <#root as #packed>::Width>
+                    });
+                }
+            }
+        }
+    }
+
+    let op_impls = ops.iter().map(|op| expand_op(&root, &name, &generics, op)).collect::<Vec<_>>();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let self_path = quote!{ #name };
+    let vector_expr = shape.expr(&self_path);
+    let vector_ref_pat = shape.ref_pat(&self_path, quote!{ ref });
+    let vector_mut_pat = shape.ref_pat(&self_path, quote!{ ref mut });
+    let scalar_expr = shape.expr(&scalar_path);
+
+    let base_expr = vlist(&root, shape.bindings.iter().map(|b| quote!{ #b }));
+    let base_refs = vlist(&root, shape.bindings.iter().map(|b| quote!{ #gats::as_packed_ref(#b) }));
+    let base_muts = vlist(&root, shape.bindings.iter().map(|b| quote!{ #gats::as_packed_mut(#b) }));
+
+    let base_vector = vlist_type(&root, shape.types.iter().map(|ty| quote!{ #ty }));
+    let base_scalar = vlist_type(&root, shape.types.iter().map(|ty| quote!{ <#ty as #packed>::Scalar }));
+
+    quote!{
+        impl #impl_generics #root::packed::CustomPacked for #name #ty_generics #where_clause {
+            type CustomScalar = #scalar_type;
+            type BaseScalar = #base_scalar;
+            type BaseVector = #base_vector;
+
+            #[inline(always)]
+            fn vector_into_base(vector: Self) -> Self::BaseVector {
+                let #vector_expr = vector;
+                #base_expr
+            }
+
+            #[inline(always)]
+            fn vector_from_base(vector: Self::BaseVector) -> Self {
+                let #base_expr = vector;
+                #vector_expr
+            }
+
+            #[inline(always)]
+            fn scalar_into_base(scalar: Self::CustomScalar) -> Self::BaseScalar {
+                let #scalar_expr = scalar;
+                #base_expr
+            }
+
+            #[inline(always)]
+            fn scalar_from_base(scalar: Self::BaseScalar) -> Self::CustomScalar {
+                let #base_expr = scalar;
+                #scalar_expr
+            }
+
+            #[inline(always)]
+            fn vector_as_base<'__a>(vector: &'__a Self) -> <Self::BaseVector as #gats<'__a>>::Ref {
+                let #vector_ref_pat = *vector;
+                #base_refs
+            }
+
+            #[inline(always)]
+            fn vector_as_base_mut<'__a>(vector: &'__a mut Self) -> <Self::BaseVector as #gats<'__a>>::RefMut {
+                let #vector_mut_pat = *vector;
+                #base_muts
+            }
+        }
+
+        #(#op_impls)*
+    }
+}
+
+/// The type parameter that `ty` consists of, if it is just one of `params`.
+fn as_param(ty: &Type, params: &[Ident]) -> Option<Ident> {
+    match *ty {
+        Type::Path(ref ty) if ty.qself.is_none() && ty.path.leading_colon.is_none() && ty.path.segments.len() == 1 => {
+            let segment = ty.path.segments.iter().next().unwrap();
+            match segment.arguments {
+                PathArguments::None => params.iter().find(|&&param| param == segment.ident).cloned(),
+                _ => None,
+            }
+        },
+        Type::Paren(ref ty) => as_param(&ty.elem, params),
+        Type::Group(ref ty) => as_param(&ty.elem, params),
+        _ => None,
+    }
+}
+
+/// The type parameters (out of `params`) that appear anywhere in `ty`, in order of appearance.
+fn mentioned_params(ty: &Type, params: &[Ident]) -> Vec<Ident> {
+    let mut out = vec![];
+    visit_type(ty, params, &mut out);
+    out
+}
+
+fn visit_type(ty: &Type, params: &[Ident], out: &mut Vec<Ident>) {
+    match *ty {
+        Type::Path(ref ty) => {
+            if let Some(ref qself) = ty.qself {
+                visit_type(&qself.ty, params, out);
+            }
+            visit_path(&ty.path, params, out);
+        },
+        Type::Slice(ref ty) => visit_type(&ty.elem, params, out),
+        Type::Array(ref ty) => visit_type(&ty.elem, params, out),
+        Type::Ptr(ref ty) => visit_type(&ty.elem, params, out),
+        Type::Reference(ref ty) => visit_type(&ty.elem, params, out),
+        Type::Paren(ref ty) => visit_type(&ty.elem, params, out),
+        Type::Group(ref ty) => visit_type(&ty.elem, params, out),
+        Type::Tuple(ref ty) => for elem in ty.elems.iter() {
+            visit_type(elem, params, out);
+        },
+        // (none of the others can be Packed)
+        _ => {},
+    }
+}
+
+fn visit_path(path: &Path, params: &[Ident], out: &mut Vec<Ident>) {
+    // `V` or `V::Assoc`
+    if path.leading_colon.is_none() {
+        if let Some(segment) = path.segments.iter().next() {
+            if params.contains(&segment.ident) && !out.contains(&segment.ident) {
+                out.push(segment.ident);
+            }
+        }
+    }
+    for segment in path.segments.iter() {
+        match segment.arguments {
+            PathArguments::AngleBracketed(ref args) => for arg in args.args.iter() {
+                match *arg {
+                    GenericArgument::Type(ref ty) => visit_type(ty, params, out),
+                    GenericArgument::Binding(ref binding) => visit_type(&binding.ty, params, out),
+                    _ => {},
+                }
+            },
+            PathArguments::Parenthesized(ref args) => {
+                for input in args.inputs.iter() {
+                    visit_type(input, params, out);
+                }
+                if let ReturnType::Type(_, ref output) = args.output {
+                    visit_type(output, params, out);
+                }
+            },
+            PathArguments::None => {},
+        }
+    }
+}
+
+/// An element-wise `std::ops` impl that goes through the `BaseVector`.
+fn expand_op(root: &Tokens, name: &Ident, generics: &syn::Generics, op: &Ident) -> Tokens {
+    let (method, binary) = op_method(op.as_ref()).expect("(bug) unchecked operator");
+    let method = Ident::new(method, Span::call_site());
+    let custom = quote!{ <Self as #root::packed::CustomPacked> };
+
+    let mut generics = generics.clone();
+    generics.make_where_clause().predicates.push(parse_quote!{
+        #custom::BaseVector: ::std::ops::#op<Output = #custom::BaseVector>
+    });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let (params, args) = match binary {
+        true => (quote!{ self, other: Self }, quote!{ #custom::vector_into_base(self), #custom::vector_into_base(other) }),
+        false => (quote!{ self }, quote!{ #custom::vector_into_base(self) }),
+    };
+
+    quote!{
+        impl #impl_generics ::std::ops::#op for #name #ty_generics #where_clause {
+            type Output = Self;
+
+            #[inline(always)]
+            fn #method(#params) -> Self {
+                #custom::vector_from_base(::std::ops::#op::#method(#args))
+            }
+        }
+    }
+}